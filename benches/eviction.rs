@@ -0,0 +1,60 @@
+// Compares strict and sampled eviction on a skewed insert/lookup workload.
+// Run with `cargo bench --bench eviction`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use lru_rs::{LRUCache, SampledLRUCache};
+
+const CAP: usize = 256;
+const OPS: u64 = 2_000_000;
+
+// A cheap, deterministic stream of keys where low keys are more common
+fn keys() -> impl Iterator<Item = u64> {
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+
+    (0..OPS).map(move |_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+
+        (state % 1024) * (state % 1024) / 1024
+    })
+}
+
+fn bench<F>(name: &str, mut access: F)
+where
+    F: FnMut(u64) -> bool,
+{
+    let start = Instant::now();
+    let hits = keys().filter(|&key| access(black_box(key))).count();
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<8} {:>8.2?} total, {:>6.1} ns/op, {:>5.1}% hits",
+        name,
+        elapsed,
+        elapsed.as_nanos() as f64 / OPS as f64,
+        hits as f64 * 100.0 / OPS as f64,
+    );
+}
+
+fn main() {
+    let mut strict = LRUCache::<u64, CAP>::new();
+    bench("strict", |key| {
+        let hit = strict.touch(|x| *x == key);
+        if !hit {
+            strict.insert(key);
+        }
+        hit
+    });
+
+    let mut sampled = SampledLRUCache::<u64, CAP>::new();
+    bench("sampled", |key| {
+        let hit = sampled.touch(|x| *x == key);
+        if !hit {
+            sampled.insert(key);
+        }
+        hit
+    });
+}
@@ -0,0 +1,61 @@
+// Measures workloads dominated by walking the recency list: full scans that
+// test every value on a miss, and rank lookups that only follow the links.
+// The list order is shuffled first so walks jump around the slots, as they
+// do in a cache that has seen real traffic. Run with `cargo bench --bench
+// walk`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use lru_rs::LRUCache;
+
+const STEPS: usize = 50_000_000;
+
+fn bench<T, const C: usize>(name: &str, make: fn(u32) -> T)
+where
+    T: PartialEq,
+{
+    let mut cache = LRUCache::<T, C>::boxed();
+    cache.extend((0..C as u32).map(make));
+
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    for _ in 0..C {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        cache.move_to_front_index(state as usize % C);
+    }
+
+    let reps = STEPS / C;
+    let absent = make(C as u32);
+
+    let start = Instant::now();
+    for _ in 0..reps {
+        black_box(cache.contains(|x| *x == absent));
+    }
+    let scan = start.elapsed();
+
+    // Moving the back to the front keeps the walk the full length
+    let start = Instant::now();
+    for _ in 0..reps {
+        black_box(cache.move_to_front_index(C - 1));
+    }
+    let links = start.elapsed();
+
+    println!(
+        "{:>9} capacity {:>5}: miss scan {:>5.2} ns/entry, link walk {:>5.2} ns/entry",
+        name,
+        C,
+        scan.as_nanos() as f64 / (reps * C) as f64,
+        links.as_nanos() as f64 / (reps * C) as f64,
+    );
+}
+
+fn main() {
+    bench::<u32, 256>("u32", |i| i);
+    bench::<u32, 4096>("u32", |i| i);
+    bench::<u32, 16384>("u32", |i| i);
+    bench::<[u64; 16], 256>("[u64; 16]", |i| [i.into(); 16]);
+    bench::<[u64; 16], 4096>("[u64; 16]", |i| [i.into(); 16]);
+    bench::<[u64; 16], 16384>("[u64; 16]", |i| [i.into(); 16]);
+}
@@ -0,0 +1,152 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+#[cfg(feature = "alloc")]
+use crate::DynLRUCache;
+use crate::{AccessPolicy, LRUCache, ScanCache};
+
+/// The operations shared by every cache representation, usable as a trait
+/// object so the representation can be chosen at runtime.
+pub trait Cache<T> {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn capacity(&self) -> usize;
+
+    fn clear(&mut self);
+
+    /// Returns the most recently used value.
+    fn front(&self) -> Option<&T>;
+
+    /// Accesses the first value matching `pred`, returning whether there was
+    /// one.
+    fn touch(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> bool;
+
+    /// Accesses the first value matching `pred` and returns it.
+    fn get(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> Option<&T>;
+
+    /// Inserts `val` as the most recently used value, returning the evicted
+    /// value if the cache was full.
+    fn insert(&mut self, val: T) -> Option<T>;
+}
+
+// Up to this capacity, shifting a small array beats maintaining links
+#[cfg(feature = "alloc")]
+const SCAN_THRESHOLD: usize = 16;
+
+/// Creates a cache of capacity `C` behind the `Cache` trait, picking the
+/// representation expected to be fastest for that size: a `ScanCache` for
+/// small capacities, an `LRUCache` otherwise.
+#[cfg(feature = "alloc")]
+pub fn with_capacity_hint<T, const C: usize>() -> Box<dyn Cache<T>>
+where
+    T: 'static,
+{
+    if C <= SCAN_THRESHOLD {
+        Box::new(ScanCache::<T, C>::new())
+    } else {
+        LRUCache::<T, C>::boxed()
+    }
+}
+
+impl<T, const C: usize, P: AccessPolicy> Cache<T> for LRUCache<T, C, P> {
+    fn len(&self) -> usize {
+        LRUCache::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        LRUCache::capacity(self)
+    }
+
+    fn clear(&mut self) {
+        LRUCache::clear(self)
+    }
+
+    fn front(&self) -> Option<&T> {
+        LRUCache::front(self)
+    }
+
+    fn touch(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> bool {
+        LRUCache::touch(self, pred)
+    }
+
+    fn get(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> Option<&T> {
+        self.fetch_ref(pred)
+    }
+
+    fn insert(&mut self, val: T) -> Option<T> {
+        LRUCache::insert(self, val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Cache<T> for DynLRUCache<T> {
+    fn len(&self) -> usize {
+        DynLRUCache::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        DynLRUCache::capacity(self)
+    }
+
+    fn clear(&mut self) {
+        DynLRUCache::clear(self)
+    }
+
+    fn front(&self) -> Option<&T> {
+        DynLRUCache::front(self)
+    }
+
+    fn touch(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> bool {
+        DynLRUCache::touch(self, pred)
+    }
+
+    fn get(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> Option<&T> {
+        if DynLRUCache::touch(self, pred) {
+            DynLRUCache::front(self)
+        } else {
+            None
+        }
+    }
+
+    fn insert(&mut self, val: T) -> Option<T> {
+        DynLRUCache::insert(self, val)
+    }
+}
+
+impl<T, const C: usize> Cache<T> for ScanCache<T, C> {
+    fn len(&self) -> usize {
+        ScanCache::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        ScanCache::capacity(self)
+    }
+
+    fn clear(&mut self) {
+        ScanCache::clear(self)
+    }
+
+    fn front(&self) -> Option<&T> {
+        ScanCache::front(self)
+    }
+
+    fn touch(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> bool {
+        ScanCache::touch(self, pred)
+    }
+
+    fn get(&mut self, pred: &mut dyn FnMut(&T) -> bool) -> Option<&T> {
+        if ScanCache::touch(self, pred) {
+            ScanCache::front(self)
+        } else {
+            None
+        }
+    }
+
+    fn insert(&mut self, val: T) -> Option<T> {
+        ScanCache::insert(self, val)
+    }
+}
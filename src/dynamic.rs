@@ -0,0 +1,197 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::list::Links;
+use crate::{Iter, Node, SlotsMut};
+
+/// An LRU cache whose capacity is chosen at runtime, backed by a `Vec`.
+pub struct DynLRUCache<T> {
+    vals: Vec<T>,
+    nodes: Vec<Node>,
+    capacity: usize,
+    links: Links,
+}
+
+impl<T> Clone for DynLRUCache<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            vals: self.vals.clone(),
+            nodes: self.nodes.clone(),
+            capacity: self.capacity,
+            links: self.links,
+        }
+    }
+}
+
+impl<T> fmt::Debug for DynLRUCache<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynLRUCache")
+            .field("capacity", &self.capacity)
+            .field("head", &self.links.head)
+            .field("tail", &self.links.tail)
+            .field("vals", &self.vals)
+            .field("nodes", &self.nodes)
+            .finish()
+    }
+}
+
+impl<T> DynLRUCache<T> {
+    /// Creates an empty cache holding up to `capacity` values.
+    ///
+    /// Panics with "Capacity overflow" if `capacity` exceeds `isize::MAX`,
+    /// beyond which no `Vec` can grow and the link indices could overflow.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity <= isize::MAX as usize, "Capacity overflow");
+
+        DynLRUCache {
+            vals: Vec::new(),
+            nodes: Vec::new(),
+            capacity,
+            links: Links::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.length == 0
+    }
+
+    /// The logical capacity: how many values are held before inserts start
+    /// evicting. Storage for them is allocated as needed, see `reserve`.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// How many values fit in the current backing allocation without
+    /// reallocating. Unrelated to when values are evicted.
+    pub fn allocated_capacity(&self) -> usize {
+        self.vals.capacity()
+    }
+
+    /// Preallocates room for at least `additional` more values, as
+    /// `Vec::reserve`, to avoid reallocating while the cache warms up.
+    /// Only the backing allocation grows; `capacity` is unchanged.
+    pub fn reserve(&mut self, additional: usize) {
+        self.vals.reserve(additional);
+        self.nodes.reserve(additional);
+    }
+
+    pub fn clear(&mut self) {
+        self.vals.clear();
+        self.nodes.clear();
+        self.links = Links::new();
+    }
+
+    /// Like `clear`, but also returns the backing allocation to the
+    /// allocator. `capacity` is unchanged.
+    pub fn clear_and_shrink(&mut self) {
+        self.clear();
+        self.vals.shrink_to_fit();
+        self.nodes.shrink_to_fit();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.links.iter(&self.vals, &self.nodes)
+    }
+
+    fn slots_mut(&mut self) -> SlotsMut<'_, T> {
+        self.links.slots_mut(&mut self.vals, &self.nodes)
+    }
+
+    pub fn items(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        let head = self.links.head?;
+
+        Some(&self.vals[head])
+    }
+
+    pub fn touch<F>(&mut self, mut pred: F) -> bool
+    where
+        F: FnMut(&T) -> bool,
+    {
+        match self.slots_mut().find(|(_, x)| pred(x)) {
+            Some((i, _)) => {
+                self.links.touch_index(&mut self.nodes, i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn lookup<F, R>(&mut self, mut pred: F) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        let mut result = None;
+
+        for (i, entry) in self.slots_mut() {
+            if let Some(r) = pred(entry) {
+                result = Some((i, r));
+                break;
+            }
+        }
+
+        match result {
+            None => None,
+            Some((i, r)) => {
+                self.links.touch_index(&mut self.nodes, i);
+                Some(r)
+            }
+        }
+    }
+
+    /// Inserts `val` at the front, returning the evicted least-recently-used
+    /// value if the cache was full.
+    pub fn insert(&mut self, val: T) -> Option<T> {
+        self.links
+            .insert(&mut self.vals, &mut self.nodes, self.capacity, val)
+    }
+
+    /// Changes the capacity to `new_cap`.
+    ///
+    /// Growing keeps every entry. Shrinking below the current length evicts
+    /// least-recently-used entries until the rest fit, and returns them in
+    /// eviction order (least recently used first). Shrinking to 0 empties the
+    /// cache. Panics on an oversized `new_cap`, as `with_capacity` does.
+    pub fn resize(&mut self, new_cap: usize) -> Vec<T> {
+        assert!(new_cap <= isize::MAX as usize, "Capacity overflow");
+
+        let evicted = self.shrink_to(new_cap);
+        self.capacity = new_cap;
+
+        evicted
+    }
+
+    /// Evicts least-recently-used entries until at most `n` are left, and
+    /// returns them in eviction order (least recently used first).
+    ///
+    /// Unlike `resize`, the capacity is unchanged, so the cache can fill up
+    /// again; the backing allocation is kept too.
+    pub fn shrink_to(&mut self, n: usize) -> Vec<T> {
+        let mut evicted = Vec::with_capacity(self.len().saturating_sub(n));
+
+        while let Some(tail) = self.links.tail.filter(|_| self.len() > n) {
+            evicted.push(
+                self.links
+                    .remove_index(&mut self.vals, &mut self.nodes, tail),
+            );
+        }
+
+        evicted
+    }
+}
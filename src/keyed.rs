@@ -0,0 +1,130 @@
+use core::fmt;
+use core::hash::Hash;
+use core::mem;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::LRUCache;
+
+/// An LRU cache addressed by key, with O(1) `get` and `put`.
+///
+/// Keys are shared between the entry list and the lookup map through an
+/// `Arc`, so only `K: Eq + Hash` is required.
+pub struct KeyedLRUCache<K, V, const C: usize> {
+    // Recency-ordered list of (key, value) pairs
+    cache: LRUCache<(Arc<K>, V), C>,
+
+    // Index of the entry holding each key
+    index: HashMap<Arc<K>, usize>,
+}
+
+impl<K, V, const C: usize> Default for KeyedLRUCache<K, V, C> {
+    fn default() -> Self {
+        Self {
+            cache: LRUCache::default(),
+            index: HashMap::with_capacity(C),
+        }
+    }
+}
+
+impl<K, V, const C: usize> fmt::Debug for KeyedLRUCache<K, V, C>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedLRUCache")
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+impl<K, V, const C: usize> Extend<(K, V)> for KeyedLRUCache<K, V, C>
+where
+    K: Eq + Hash,
+{
+    /// Puts every pair in order, so a repeated key ends up with its last
+    /// value.
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, val) in iter {
+            self.put(key, val);
+        }
+    }
+}
+
+impl<K, V, const C: usize> KeyedLRUCache<K, V, C>
+where
+    K: Eq + Hash,
+{
+    // `get_or_insert_with` returns the value it puts, which a zero-capacity
+    // cache cannot keep, so it refuses one at compile time.
+    const NONZERO_CHECK: () = assert!(C > 0, "Capacity must be non-zero");
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Returns the value for `key`, moving it to the front.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let index = *self.index.get(key)?;
+        self.cache.links.touch_index(&mut self.cache.nodes, index);
+
+        Some(&self.cache.vals[index].1)
+    }
+
+    /// Inserts `val` under `key` at the front.
+    ///
+    /// Returns the value displaced by this call: the previous value for
+    /// `key` if it was already cached, otherwise the evicted
+    /// least-recently-used value when the cache was full.
+    pub fn put(&mut self, key: K, val: V) -> Option<V> {
+        if let Some(&index) = self.index.get(&key) {
+            self.cache.links.touch_index(&mut self.cache.nodes, index);
+
+            return Some(mem::replace(&mut self.cache.vals[index].1, val));
+        }
+
+        let key = Arc::new(key);
+        let evicted = self.cache.insert((Arc::clone(&key), val));
+
+        if let Some((old_key, _)) = &evicted {
+            self.index.remove(old_key);
+        }
+
+        // A zero-capacity cache evicts the new entry straight away
+        if let Some(head) = self.cache.links.head {
+            self.index.insert(key, head);
+        }
+
+        evicted.map(|(_, val)| val)
+    }
+
+    /// Returns the value for `key`, first inserting the result of `f` if it
+    /// is missing, and moves it to the front either way.
+    ///
+    /// Fails to compile for a zero-capacity cache, where `put` evicts the
+    /// new entry straight away and there is no value to return.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> &V
+    where
+        F: FnOnce() -> V,
+    {
+        let () = Self::NONZERO_CHECK;
+
+        if let Some(&index) = self.index.get(&key) {
+            self.cache.links.touch_index(&mut self.cache.nodes, index);
+
+            return &self.cache.vals[index].1;
+        }
+
+        self.put(key, f());
+
+        &self.cache.front().expect("cache is non-empty after put").1
+    }
+}
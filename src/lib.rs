@@ -1,14 +1,68 @@
 // From https://dev.to/seanchen1991/implementing-an-lru-cache-in-rust-33pp
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::cmp::Ordering;
 use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{ControlFlow, Deref, DerefMut, Index, IndexMut};
+#[cfg(feature = "alloc")]
+use core::ptr;
 
 use arrayvec::ArrayVec;
 
-#[derive(Debug, Clone, Copy)]
-pub struct Entry<T> {
-    // Value stored in this entry
-    val: T,
+mod cache;
+#[cfg(feature = "alloc")]
+mod dynamic;
+#[cfg(feature = "std")]
+mod keyed;
+mod list;
+mod policy;
+pub mod prelude;
+mod sampled;
+mod scan;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "std")]
+mod sync;
+#[cfg(feature = "std")]
+mod ttl;
+#[cfg(feature = "alloc")]
+mod weighted;
+
+#[cfg(feature = "alloc")]
+pub use cache::with_capacity_hint;
+pub use cache::Cache;
+#[cfg(feature = "alloc")]
+pub use dynamic::DynLRUCache;
+#[cfg(feature = "std")]
+pub use keyed::KeyedLRUCache;
+#[cfg(feature = "alloc")]
+pub use policy::AdmissionPolicy;
+pub use policy::{Access, AccessPolicy, Lru, StepUp};
+pub use sampled::SampledLRUCache;
+pub use scan::ScanCache;
+#[cfg(feature = "std")]
+pub use sync::SyncLRUCache;
+#[cfg(feature = "std")]
+pub use ttl::{Clock, SystemClock, TtlLRUCache};
+#[cfg(feature = "alloc")]
+pub use weighted::WeightedLRUCache;
 
+use list::Links;
+
+// The links of the value in the same slot, an implementation detail the
+// iterators never expose. Kept apart from the values so walking the list
+// does not drag them through the cache.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Node {
     // Index of prev
     prev: usize,
 
@@ -16,241 +70,1548 @@ pub struct Entry<T> {
     next: usize,
 }
 
-pub struct LRUCache<T, const CAP: usize> {
-    entries: ArrayVec<Entry<T>, CAP>,
-    head: usize,
-    tail: usize,
-    length: usize,
+// Called with each value evicted to make room for an insert
+#[cfg(feature = "alloc")]
+type EvictionHook<T> = Box<dyn FnMut(&T) + Send + Sync>;
+
+#[cfg(feature = "alloc")]
+type Admission<T> = Box<dyn AdmissionPolicy<T> + Send + Sync>;
+
+/// A fixed-capacity LRU cache. How accesses reorder values is decided by the
+/// `AccessPolicy` `P`, strict LRU by default.
+///
+/// Any capacity is accepted, including zero: such a cache never stores
+/// anything and hands every inserted value straight back as evicted.
+///
+/// ```
+/// use lru_rs::LRUCache;
+///
+/// let mut cache = LRUCache::<i32, 0>::new();
+///
+/// assert_eq!(cache.insert(1), Some(1));
+/// assert!(cache.is_empty());
+/// assert_eq!(cache.front(), None);
+/// ```
+///
+/// Methods that return a reference to the value they insert, such as
+/// `get_or_insert_with`, are rejected at compile time for a zero capacity:
+///
+/// ```compile_fail
+/// use lru_rs::LRUCache;
+///
+/// let mut cache = LRUCache::<i32, 0>::new();
+///
+/// cache.get_or_insert_with(|x| *x == 1, || 1);
+/// ```
+pub struct LRUCache<T, const CAP: usize, P = Lru> {
+    vals: ArrayVec<T, CAP>,
+    nodes: ArrayVec<Node, CAP>,
+    links: Links,
+    #[cfg(feature = "alloc")]
+    on_evict: Option<EvictionHook<T>>,
+    #[cfg(feature = "alloc")]
+    admission: Option<Admission<T>>,
+    #[cfg(feature = "stats")]
+    stats: CacheStats,
+    policy: PhantomData<fn() -> P>,
 }
 
-impl<T, const C: usize> Default for LRUCache<T, C> {
-    fn default() -> Self {
-        let cache = LRUCache {
-            entries: ArrayVec::<Entry<T>, C>::new(),
-            head: 0,
-            tail: 0,
-            length: 0,
-        };
+/// A cache storing its values behind a `Box`, so the inline storage holds
+/// only pointers. Suits large values, which are then cheap to move on
+/// eviction, and unsized ones such as trait objects.
+#[cfg(feature = "alloc")]
+pub type BoxedLRUCache<T, const CAP: usize, P = Lru> = LRUCache<Box<T>, CAP, P>;
 
-        assert!(cache.entries.capacity() < usize::MAX, "Capacity overflow");
+/// Counters describing how a cache has been used.
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    // Accesses that found a matching value
+    pub hits: u64,
 
-        cache
+    // Accesses that found nothing
+    pub misses: u64,
+
+    // Values evicted to make room for an insert
+    pub evictions: u64,
+}
+
+#[cfg(feature = "stats")]
+impl CacheStats {
+    const fn new() -> Self {
+        CacheStats {
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+}
+
+impl<T, const C: usize, P: AccessPolicy> Default for LRUCache<T, C, P> {
+    /// Same as `new`. The capacity is checked at compile time, so this
+    /// neither allocates nor branches.
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl<T, const C: usize> Clone for LRUCache<T, C>
+impl<T, const C: usize, P: AccessPolicy> Clone for LRUCache<T, C, P>
 where
     T: Clone,
 {
     fn clone(&self) -> Self {
         Self {
-            entries: self.entries.clone(),
-            head: self.head,
-            tail: self.tail,
-            length: self.length,
+            vals: self.vals.clone(),
+            nodes: self.nodes.clone(),
+            links: self.links,
+            #[cfg(feature = "alloc")]
+            on_evict: None,
+            #[cfg(feature = "alloc")]
+            admission: None,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+            policy: PhantomData,
+        }
+    }
+
+    // Reuses the existing entries (and any buffers their values own) instead
+    // of building a new backing store. The eviction hook and admission policy
+    // of `self` are kept.
+    fn clone_from(&mut self, source: &Self) {
+        self.vals.clone_from(&source.vals);
+        self.nodes.clone_from(&source.nodes);
+        self.links = source.links;
+
+        #[cfg(feature = "stats")]
+        {
+            self.stats = source.stats;
         }
     }
 }
 
-impl<T, const C: usize> fmt::Debug for LRUCache<T, C>
+impl<T, const C: usize, P: AccessPolicy> fmt::Debug for LRUCache<T, C, P>
 where
     T: fmt::Debug,
 {
+    // Values are listed from most to least recently used, as in
+    // `LRUCache [4, 3, 2, 1]`. The alternate form also shows the slots of the
+    // two ends, for debugging the links themselves.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("LRUCache")
-            .field("head", &self.head)
-            .field("tail", &self.tail)
-            .field("entries", &self.entries)
-            .finish()
+        if f.alternate() {
+            f.debug_struct("LRUCache")
+                .field("head", &self.links.head)
+                .field("tail", &self.links.tail)
+                .field("values", &Values(self.iter()))
+                .finish()
+        } else {
+            f.write_str("LRUCache ")?;
+            Values(self.iter()).fmt(f)
+        }
     }
 }
 
-pub struct IterMut<'a, T, const C: usize> {
-    cache: &'a mut LRUCache<T, C>,
-    pos: usize,
-    done: bool,
+// Lists the values left in an iterator.
+struct Values<'a, T>(Iter<'a, T>);
+
+impl<T> fmt::Debug for Values<'_, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.clone()).finish()
+    }
 }
 
-impl<'a, T, const C: usize> Iterator for IterMut<'a, T, C>
+// Caches are equal when they hold equal values in the same recency order,
+// regardless of which slots the values occupy.
+impl<T, const C: usize, P: AccessPolicy> PartialEq for LRUCache<T, C, P>
 where
-    T: 'a,
+    T: PartialEq,
 {
-    type Item = (usize, &'a mut T);
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const C: usize, P: AccessPolicy> Eq for LRUCache<T, C, P> where T: Eq {}
+
+// Hashes the values in recency order, as `PartialEq` compares them, so equal
+// caches hash equal. The length goes first, as for slices.
+impl<T, const C: usize, P: AccessPolicy> Hash for LRUCache<T, C, P>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+
+        for val in self.iter() {
+            val.hash(state);
+        }
+    }
+}
+
+// Indexes by recency rank: 0 is the most recently used value.
+impl<T, const C: usize, P: AccessPolicy> Index<usize> for LRUCache<T, C, P> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match self.peek_nth(index) {
+            Some(val) => val,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            ),
+        }
+    }
+}
+
+// Assigning through an index does not count as an access, so the order is
+// left as it is.
+impl<T, const C: usize, P: AccessPolicy> IndexMut<usize> for LRUCache<T, C, P> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match self.links.index_at(&self.nodes, index) {
+            Some(slot) => &mut self.vals[slot],
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            ),
+        }
+    }
+}
+
+// Inserts in iteration order, so when there are more than `C` values only
+// the last `C` remain.
+impl<T, const C: usize, P: AccessPolicy> Extend<T> for LRUCache<T, C, P> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for val in iter {
+            self.insert(val);
+        }
+    }
+}
+
+impl<T, const C: usize, P: AccessPolicy> FromIterator<T> for LRUCache<T, C, P> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut cache = Self::default();
+        cache.extend(iter);
+
+        cache
+    }
+}
+
+/// Iterator over shared references to the cached values, from most to least
+/// recently used, or the other way round when reversed.
+pub struct Iter<'a, T> {
+    vals: &'a [T],
+    nodes: &'a [Node],
+
+    // Next index to yield from the front
+    pos: usize,
+
+    // Next index to yield from the back
+    back: usize,
+
+    remaining: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            vals: self.vals,
+            nodes: self.nodes,
+            pos: self.pos,
+            back: self.back,
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
+        if self.remaining == 0 {
             return None;
         }
 
-        let entry = unsafe { &mut *(&mut self.cache.entries[self.pos] as *mut Entry<T>) };
-        let index = self.pos;
+        let val = &self.vals[self.pos];
+        self.pos = self.nodes[self.pos].next;
+        self.remaining -= 1;
+
+        Some(val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
 
-        if self.pos == self.cache.tail {
-            self.done = true;
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
 
-        self.pos = entry.next;
+        let val = &self.vals[self.back];
+        self.back = self.nodes[self.back].prev;
+        self.remaining -= 1;
 
-        Some((index, &mut entry.val))
+        Some(val)
     }
 }
 
-impl<T, const C: usize> LRUCache<T, C>
-where
-    T: Copy,
-{
-    pub fn len(&self) -> usize {
-        self.length
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+/// Owning iterator over the cached values, from most to least recently used.
+pub struct IntoIter<T, const C: usize, P = Lru> {
+    cache: LRUCache<T, C, P>,
+}
+
+impl<T, const C: usize, P: AccessPolicy> Iterator for IntoIter<T, C, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let head = self.cache.links.head?;
+
+        Some(
+            self.cache
+                .links
+                .remove_index(&mut self.cache.vals, &mut self.cache.nodes, head),
+        )
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.length == 0
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.cache.len(), Some(self.cache.len()))
+    }
+}
+
+impl<T, const C: usize, P: AccessPolicy> DoubleEndedIterator for IntoIter<T, C, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cache.pop_lru()
     }
+}
 
-    pub fn clear(&mut self) {
-        self.entries.clear();
-        self.head = 0;
-        self.tail = 0;
-        self.length = 0;
+impl<T, const C: usize, P: AccessPolicy> ExactSizeIterator for IntoIter<T, C, P> {}
+
+impl<T, const C: usize, P: AccessPolicy> IntoIterator for LRUCache<T, C, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T, C, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { cache: self }
     }
+}
 
-    fn iter_mut(&mut self) -> IterMut<T, C> {
-        IterMut {
-            pos: self.head,
-            done: self.is_empty(),
-            cache: self,
-        }
+impl<'a, T, const C: usize, P: AccessPolicy> IntoIterator for &'a LRUCache<T, C, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
+}
 
-    pub fn items(&mut self) -> Vec<T> {
-        let x = self.iter_mut();
+impl<'a, T, const C: usize, P: AccessPolicy> IntoIterator for &'a mut LRUCache<T, C, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
 
-        x.map(|(_, x)| x.clone()).collect()
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
+}
 
-    pub fn front(&self) -> Option<&T> {
-        self.entries.get(self.head).map(|e| &e.val)
+/// Draining iterator over the cached values, from most to least recently
+/// used. Whatever is left when it is dropped is removed too.
+pub struct Drain<'a, T, const C: usize> {
+    vals: &'a mut ArrayVec<T, C>,
+    nodes: &'a mut ArrayVec<Node, C>,
+    links: &'a mut Links,
+}
+
+impl<T, const C: usize> Iterator for Drain<'_, T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let head = self.links.head?;
+
+        Some(self.links.remove_index(self.vals, self.nodes, head))
     }
 
-    pub fn front_mut(&mut self) -> Option<&mut T> {
-        self.entries.get_mut(self.head).map(|e| &mut e.val)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.links.length, Some(self.links.length))
     }
+}
 
-    fn push_front(&mut self, index: usize) {
-        if self.entries.len() == 1 {
-            self.tail = index;
-        } else {
-            self.entries[index].next = self.head;
-            self.entries[self.head].prev = index;
-        }
+impl<T, const C: usize> DoubleEndedIterator for Drain<'_, T, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let tail = self.links.tail?;
 
-        self.head = index;
+        Some(self.links.remove_index(self.vals, self.nodes, tail))
     }
+}
 
-    fn pop_back(&mut self) -> usize {
-        let old_tail = self.tail;
-        let new_tail = self.entries[old_tail].prev;
-        self.tail = new_tail;
+impl<T, const C: usize> ExactSizeIterator for Drain<'_, T, C> {}
 
-        old_tail
+impl<T, const C: usize> Drop for Drain<'_, T, C> {
+    fn drop(&mut self) {
+        self.vals.clear();
+        self.nodes.clear();
+        *self.links = Links::new();
     }
+}
+
+/// Iterator removing the values matching a predicate, from most to least
+/// recently used. Created by `LRUCache::extract_if`.
+pub struct ExtractIf<'a, T, const C: usize, F> {
+    vals: &'a mut ArrayVec<T, C>,
+    nodes: &'a mut ArrayVec<Node, C>,
+    links: &'a mut Links,
 
-    fn remove(&mut self, index: usize) {
-        assert!(self.length > 0);
+    // Next slot to test, only meaningful while `remaining` is non-zero
+    pos: usize,
 
-        let prev = self.entries[index].prev;
-        let next = self.entries[index].next;
+    // Number of values not yet tested
+    remaining: usize,
 
-        if index == self.head {
-            self.head = next;
-        } else {
-            self.entries[prev].next = next;
+    pred: F,
+}
+
+impl<T, const C: usize, F> Iterator for ExtractIf<'_, T, C, F>
+where
+    F: FnMut(&T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            self.remaining -= 1;
+
+            let pos = self.pos;
+            let next = self.nodes[pos].next;
+
+            if !(self.pred)(&self.vals[pos]) {
+                self.pos = next;
+                continue;
+            }
+
+            let last = self.vals.len() - 1;
+            let val = self.links.remove_index(self.vals, self.nodes, pos);
+
+            // The physically last entry was moved into `pos`
+            self.pos = if next == last { pos } else { next };
+
+            return Some(val);
         }
 
-        if index == self.tail {
-            self.tail = prev;
-        } else {
-            self.entries[next].prev = prev;
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+// Walks the list like `IterMut`, also yielding the slot of each value.
+pub(crate) struct SlotsMut<'a, T> {
+    // Base of the values; every yielded reference is derived from this one
+    // pointer, so none of them invalidates another.
+    vals: *mut T,
+    len: usize,
+    nodes: &'a [Node],
+    pos: usize,
+    back: usize,
+    remaining: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<T: Send> Send for SlotsMut<'_, T> {}
+unsafe impl<T: Sync> Sync for SlotsMut<'_, T> {}
+
+impl<'a, T> Iterator for SlotsMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
 
-        self.length -= 1;
+        let index = self.pos;
+        assert!(index < self.len);
+
+        // SAFETY: `index` is in bounds. The two cursors walk towards each
+        // other over the `remaining` live entries between them, so each entry
+        // is visited at most once and the value reference handed out here
+        // never aliases another one. The links live in a separate slice, so
+        // following them never touches a value.
+        self.pos = self.nodes[index].next;
+        let val = unsafe { &mut *self.vals.add(index) };
+        self.remaining -= 1;
+
+        Some((index, val))
     }
 
-    #[inline]
-    fn touch_index(&mut self, index: usize) {
-        if index != self.head {
-            self.remove(index);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
 
-            self.length += 1;
-            self.push_front(index);
+impl<T> DoubleEndedIterator for SlotsMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
+
+        let index = self.back;
+        assert!(index < self.len);
+
+        // SAFETY: as in `next`.
+        self.back = self.nodes[index].prev;
+        let val = unsafe { &mut *self.vals.add(index) };
+        self.remaining -= 1;
+
+        Some((index, val))
     }
+}
+
+/// Iterator over mutable references to the cached values, from most to least
+/// recently used, or the other way round when reversed.
+///
+/// Each value is yielded at most once, so all the references handed out can
+/// be held at the same time. The order is not changed.
+pub struct IterMut<'a, T> {
+    slots: SlotsMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots.next().map(|(_, val)| val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.slots.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.slots.next_back().map(|(_, val)| val)
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+/// A view into the cache after a search by `LRUCache::entry`, which counted
+/// as an access to any match.
+pub struct CacheEntry<'a, T, const C: usize, P = Lru> {
+    cache: &'a mut LRUCache<T, C, P>,
+    // Slot of the match, if the search found one
+    found: Option<usize>,
+}
 
-    pub fn touch<F>(&mut self, mut pred: F) -> bool
+impl<'a, T, const C: usize, P: AccessPolicy> CacheEntry<'a, T, C, P> {
+    /// Returns the matched value, inserting `default` first if there was no
+    /// match.
+    ///
+    /// Fails to compile for a zero-capacity cache, which cannot hold
+    /// `default`.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        self.or_insert_with(|| default)
+    }
+
+    /// Like `or_insert`, only computing the value on a miss. Refuses a zero
+    /// capacity the same way.
+    pub fn or_insert_with<F>(self, make: F) -> &'a mut T
     where
-        F: FnMut(&T) -> bool,
+        F: FnOnce() -> T,
     {
-        match self.iter_mut().find(|&(_, ref x)| pred(x)) {
-            Some((i, _)) => {
-                self.touch_index(i);
-                true
+        let () = LRUCache::<T, C, P>::NONZERO_CHECK;
+
+        let index = match self.found {
+            Some(index) => index,
+            None => {
+                self.cache.force_insert(make());
+                self.cache
+                    .links
+                    .head
+                    .expect("cache is non-empty after insert")
             }
-            None => false,
-        }
+        };
+
+        &mut self.cache.vals[index]
     }
 
-    pub fn lookup<F, R>(&mut self, mut pred: F) -> Option<R>
+    /// Calls `f` on the matched value, if any.
+    pub fn and_modify<F>(self, f: F) -> Self
     where
-        F: FnMut(&T) -> Option<R>,
+        F: FnOnce(&mut T),
     {
-        let mut result = None;
-
-        for (i, entry) in self.iter_mut() {
-            if let Some(r) = pred(entry) {
-                result = Some((i, r));
-                break;
-            }
+        if let Some(index) = self.found {
+            f(&mut self.cache.vals[index]);
         }
 
-        match result {
-            None => None,
-            Some((i, r)) => {
-                self.touch_index(i);
-                Some(r)
-            }
+        self
+    }
+}
+
+/// Mutable access to the front value through `LRUCache::front_entry`.
+///
+/// When dropped after `FrontGuard::demote`, the value is moved to the back,
+/// making it the next to be evicted; otherwise it stays at the front.
+pub struct FrontGuard<'a, T, const C: usize, P = Lru> {
+    cache: &'a mut LRUCache<T, C, P>,
+    // Slot of the front value
+    index: usize,
+    demote: bool,
+}
+
+impl<T, const C: usize, P> FrontGuard<'_, T, C, P> {
+    /// Moves the value to the back once the guard is dropped. An associated
+    /// function, so it cannot shadow a method of `T`.
+    pub fn demote(this: &mut Self) {
+        this.demote = true;
+    }
+}
+
+impl<T, const C: usize, P> Deref for FrontGuard<'_, T, C, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.cache.vals[self.index]
+    }
+}
+
+impl<T, const C: usize, P> DerefMut for FrontGuard<'_, T, C, P> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.cache.vals[self.index]
+    }
+}
+
+impl<T, const C: usize, P> Drop for FrontGuard<'_, T, C, P> {
+    fn drop(&mut self) {
+        if self.demote {
+            self.cache
+                .links
+                .demote_index(&mut self.cache.nodes, self.index);
         }
     }
+}
 
-    pub fn insert(&mut self, val: T) {
-        let entry = Entry {
-            val,
-            prev: 0,
-            next: 0,
-        };
+impl<T, const C: usize, P: AccessPolicy> LRUCache<T, C, P> {
+    // Checked once per capacity at compile time rather than on every
+    // construction.
+    const CAPACITY_CHECK: () = assert!(C < usize::MAX, "Capacity overflow");
 
-        let new_head = if self.length == self.entries.capacity() {
-            let last_index = self.pop_back();
-            self.entries[last_index] = entry;
+    // Methods returning a reference to the value they insert have nowhere to
+    // put it in a zero-capacity cache, so they refuse one at compile time.
+    const NONZERO_CHECK: () = assert!(C > 0, "Capacity must be non-zero");
 
-            last_index
-        } else {
-            self.entries.push(entry);
-            self.length += 1;
+    // `boxed` relies on an all-zero `ArrayVec` being an empty one. That holds
+    // for any layout made of just an integer length and the slots: a zero
+    // length means no slot is ever read, and the slots are `MaybeUninit`, so
+    // any bytes will do. arrayvec does not document its layout, so check at
+    // compile time that beyond the slots there is only room for a length and
+    // padding, and no other state a zero could make invalid.
+    #[cfg(feature = "alloc")]
+    const ZEROED_IS_EMPTY: () = {
+        use core::mem::{align_of, size_of};
 
-            self.entries.len() - 1
-        };
+        assert!(
+            size_of::<ArrayVec<T, C>>()
+                <= size_of::<[T; C]>() + size_of::<usize>() + align_of::<ArrayVec<T, C>>(),
+            "unexpected ArrayVec layout"
+        );
+        assert!(
+            size_of::<ArrayVec<Node, C>>()
+                <= size_of::<[Node; C]>() + size_of::<usize>() + align_of::<ArrayVec<Node, C>>(),
+            "unexpected ArrayVec layout"
+        );
+    };
+
+    /// The capacity `C`, for use where there is no instance to call
+    /// `capacity` on, such as sizing a companion array.
+    pub const CAPACITY: usize = C;
+
+    /// Creates an empty cache. Usable in `const` and `static` items.
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_CHECK;
 
-        self.push_front(new_head)
+        LRUCache {
+            vals: ArrayVec::new_const(),
+            nodes: ArrayVec::new_const(),
+            links: Links::new(),
+            #[cfg(feature = "alloc")]
+            on_evict: None,
+            #[cfg(feature = "alloc")]
+            admission: None,
+            #[cfg(feature = "stats")]
+            stats: CacheStats::new(),
+            policy: PhantomData,
+        }
     }
 
-    pub fn fetch<F>(&mut self, pred: F) -> Option<&mut T>
+    /// Creates a full cache from `items`, the first being the least recently
+    /// used and the last the most recently used, as if inserted in order.
+    pub fn from_array(items: [T; C]) -> Self {
+        let mut cache = Self::new();
+        cache.extend(items);
+
+        cache
+    }
+
+    /// Creates an empty cache that calls `hook` with every value evicted to
+    /// make room for an insert, before its slot is reused. The hook does not
+    /// fire for `clear`, explicit removals or values a zero-capacity cache
+    /// hands straight back, and is not carried over to clones.
+    #[cfg(feature = "alloc")]
+    pub fn with_eviction_hook<F>(hook: F) -> Self
     where
-        F: FnMut(&T) -> bool,
+        F: FnMut(&T) + Send + Sync + 'static,
     {
-        if self.touch(pred) {
-            self.front_mut()
-        } else {
-            None
+        LRUCache {
+            on_evict: Some(Box::new(hook)),
+            ..Self::new()
         }
     }
+
+    /// Creates an empty cache that asks `policy` before each insert into a
+    /// full cache whether the new value may evict the least recently used
+    /// one. A rejected value is handed back by `insert` and the cache is left
+    /// unchanged.
+    ///
+    /// Methods returning a reference to the value they insert, such as
+    /// `get_or_insert_with`, always admit it. The policy is not carried over
+    /// to clones.
+    #[cfg(feature = "alloc")]
+    pub fn with_admission_policy<A>(policy: A) -> Self
+    where
+        A: AdmissionPolicy<T> + Send + Sync + 'static,
+    {
+        LRUCache {
+            admission: Some(Box::new(policy)),
+            ..Self::new()
+        }
+    }
+
+    /// Creates an empty cache directly on the heap.
+    ///
+    /// The entries are stored inline, so a cache with a large capacity can
+    /// be too big for the stack; unlike `Box::new(LRUCache::new())`, this
+    /// never builds the cache on the stack first.
+    #[cfg(feature = "alloc")]
+    pub fn boxed() -> Box<Self> {
+        let () = Self::CAPACITY_CHECK;
+        let () = Self::ZEROED_IS_EMPTY;
+
+        let mut cache = Box::<Self>::new_uninit();
+        let ptr = cache.as_mut_ptr();
+
+        // SAFETY: every field is initialised before `assume_init`. The
+        // columns are zeroed in place, which `ZEROED_IS_EMPTY` argues is an
+        // empty `ArrayVec`. Writing through raw field pointers keeps the
+        // entries from ever being moved through the stack, which moving in
+        // `ArrayVec::new_const()` would do in unoptimised builds.
+        let cache = unsafe {
+            ptr::addr_of_mut!((*ptr).vals).write_bytes(0, 1);
+            ptr::addr_of_mut!((*ptr).nodes).write_bytes(0, 1);
+            ptr::addr_of_mut!((*ptr).links).write(Links::new());
+            ptr::addr_of_mut!((*ptr).on_evict).write(None);
+            ptr::addr_of_mut!((*ptr).admission).write(None);
+            #[cfg(feature = "stats")]
+            ptr::addr_of_mut!((*ptr).stats).write(CacheStats::new());
+            ptr::addr_of_mut!((*ptr).policy).write(PhantomData);
+
+            cache.assume_init()
+        };
+        debug_assert!(cache.vals.is_empty() && cache.nodes.is_empty());
+
+        cache
+    }
+
+    /// Moves the values into a cache of capacity `N`, keeping their recency
+    /// order. When shrinking below the current length, the least recently
+    /// used values are dropped. Any eviction hook, admission policy and stats
+    /// carry over.
+    #[cfg_attr(not(feature = "alloc"), allow(unused_mut))]
+    pub fn resize_into<const N: usize>(mut self) -> LRUCache<T, N, P> {
+        let mut resized = LRUCache::new();
+
+        #[cfg(feature = "alloc")]
+        {
+            resized.on_evict = self.on_evict.take();
+            resized.admission = self.admission.take();
+        }
+        #[cfg(feature = "stats")]
+        {
+            resized.stats = self.stats;
+        }
+
+        // Insert the survivors least recently used first, so the front ends
+        // up at the front
+        for val in self.into_iter().take(N).rev() {
+            resized
+                .links
+                .insert(&mut resized.vals, &mut resized.nodes, N, val);
+        }
+
+        resized
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.length == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        self.vals.capacity()
+    }
+
+    /// Returns whether the next `insert` will evict a value.
+    pub fn is_full(&self) -> bool {
+        self.links.length == self.capacity()
+    }
+
+    /// How many more values fit before inserts start evicting.
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.links.length
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::new();
+    }
+
+    #[inline]
+    fn record_access(&mut self, hit: bool) {
+        #[cfg(feature = "stats")]
+        if hit {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+
+        #[cfg(not(feature = "stats"))]
+        let _ = hit;
+    }
+
+    pub fn clear(&mut self) {
+        self.vals.clear();
+        self.nodes.clear();
+        self.links = Links::new();
+    }
+
+    /// Removes every value, yielding them from most to least recently used.
+    /// The cache is empty once the iterator is dropped, even if it was not
+    /// fully consumed.
+    pub fn drain(&mut self) -> Drain<'_, T, C> {
+        Drain {
+            vals: &mut self.vals,
+            nodes: &mut self.nodes,
+            links: &mut self.links,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.links.iter(&self.vals, &self.nodes)
+    }
+
+    /// Iterates over the values paired with their recency rank, 0 being the
+    /// most recently used.
+    pub fn ranked(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.iter().enumerate()
+    }
+
+    /// Iterates over mutable references to the values, from most to least
+    /// recently used, without changing the order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            slots: self.slots_mut(),
+        }
+    }
+
+    fn slots_mut(&mut self) -> SlotsMut<'_, T> {
+        self.links.slots_mut(&mut self.vals, &self.nodes)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn items(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Clones the values and sorts them with `cmp`, regardless of their
+    /// recency. Values comparing equal stay in recency order.
+    #[cfg(feature = "alloc")]
+    pub fn to_sorted_vec_by<F>(&self, cmp: F) -> Vec<T>
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut items = self.items();
+        items.sort_by(cmp);
+
+        items
+    }
+
+    /// References to the values from most to least recently used, without
+    /// cloning them.
+    #[cfg(feature = "alloc")]
+    pub fn as_mru_vec(&self) -> Vec<&T> {
+        self.iter().collect()
+    }
+
+    /// References to the values from least to most recently used, in
+    /// eviction order.
+    #[cfg(feature = "alloc")]
+    pub fn as_lru_vec(&self) -> Vec<&T> {
+        self.iter().rev().collect()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        let head = self.links.head?;
+
+        Some(&self.vals[head])
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        let head = self.links.head?;
+
+        Some(&mut self.vals[head])
+    }
+
+    /// Returns a guard for mutating the front value, which can also move it
+    /// to the back when done. See `FrontGuard`.
+    pub fn front_entry(&mut self) -> Option<FrontGuard<'_, T, C, P>> {
+        let index = self.links.head?;
+
+        Some(FrontGuard {
+            cache: self,
+            index,
+            demote: false,
+        })
+    }
+
+    /// Returns the front value with its recency rank, which is always 0.
+    pub fn front_ranked(&self) -> Option<(usize, &T)> {
+        self.front().map(|val| (0, val))
+    }
+
+    /// Returns the front value along with how many values are behind it.
+    pub fn front_with_rest(&self) -> Option<(&T, usize)> {
+        self.front().map(|val| (val, self.len() - 1))
+    }
+
+    /// Returns the recency rank of the first value matching `pred`, 0 being
+    /// the most recently used, without changing the order.
+    pub fn position<F>(&self, pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().position(pred)
+    }
+
+    /// Returns the least-recently-used value, the next to be evicted.
+    pub fn back(&self) -> Option<&T> {
+        let tail = self.links.tail?;
+
+        Some(&self.vals[tail])
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        let tail = self.links.tail?;
+
+        Some(&mut self.vals[tail])
+    }
+
+    /// Mutable access to the next eviction victim, guaranteed not to change
+    /// its recency: it stays at the back. The same as `back_mut`, under a
+    /// name that says so.
+    pub fn back_mut_no_touch(&mut self) -> Option<&mut T> {
+        self.back_mut()
+    }
+
+    /// Returns the first value matching `pred`, walking from most to least
+    /// recently used, without changing the recency order.
+    pub fn peek<F>(&self, mut pred: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().find(|x| pred(x))
+    }
+
+    /// Returns whether any value matches `pred`, without changing the recency
+    /// order.
+    pub fn contains<F>(&self, pred: F) -> bool
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().any(pred)
+    }
+
+    /// Returns how many values match `pred`, without changing the order.
+    pub fn count_matching<F>(&self, mut pred: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().filter(|val| pred(val)).count()
+    }
+
+    /// Calls `f` with each value from most to least recently used, stopping
+    /// as soon as it returns `Break`. Does not change the order.
+    pub fn walk<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> ControlFlow<()>,
+    {
+        for val in self.iter() {
+            if f(val).is_break() {
+                break;
+            }
+        }
+    }
+
+    /// Returns the least-recently-used value without changing the recency
+    /// order.
+    pub fn peek_lru(&self) -> Option<&T> {
+        self.back()
+    }
+
+    /// Returns the value at recency rank `n`, 0 being the most recently
+    /// used, without changing the recency order. The non-panicking
+    /// counterpart of indexing.
+    pub fn peek_nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    pub fn touch<F>(&mut self, pred: F) -> bool
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.access_index(pred).is_some()
+    }
+
+    // Finds the first value matching `pred`, counts the access and lets the
+    // policy reposition the match. Returns the slot of the match, which
+    // repositioning never changes.
+    fn access_index<F>(&mut self, pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let found = self.links.find_index(&self.vals, &self.nodes, pred);
+        self.record_access(found.is_some());
+
+        if let Some(index) = found {
+            self.on_access(index);
+        }
+
+        found
+    }
+
+    #[inline]
+    fn on_access(&mut self, index: usize) {
+        P::on_access(Access {
+            vals: &self.vals,
+            nodes: &mut self.nodes,
+            links: &mut self.links,
+            index,
+        });
+    }
+
+    /// Moves every value matching `pred` to the front and returns how many
+    /// matched.
+    ///
+    /// The matched values keep their previous order among themselves, ahead
+    /// of the rest: touching the odd values of `[5, 4, 3, 2, 1]` gives
+    /// `[5, 3, 1, 4, 2]`.
+    pub fn touch_matching<F>(&mut self, mut pred: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // Walking from the back, each match is moved in front of the ones
+        // moved before it, which were less recently used.
+        let Some(mut pos) = self.links.tail else {
+            return 0;
+        };
+        let mut matched = 0;
+
+        for _ in 0..self.links.length {
+            let prev = self.nodes[pos].prev;
+
+            if pred(&self.vals[pos]) {
+                self.links.touch_index(&mut self.nodes, pos);
+                matched += 1;
+            }
+
+            pos = prev;
+        }
+
+        matched
+    }
+
+    /// Like `touch`, returning the recency rank the match had before it was
+    /// accessed.
+    pub fn lookup_position<F>(&mut self, mut pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let found = self
+            .slots_mut()
+            .enumerate()
+            .find(|(_, (_, x))| pred(x))
+            .map(|(rank, (index, _))| (rank, index));
+        self.record_access(found.is_some());
+
+        let (rank, index) = found?;
+        self.on_access(index);
+
+        Some(rank)
+    }
+
+    /// Moves the value at recency rank `logical_pos`, 0 being the most
+    /// recently used, to the front. Returns `false`, changing nothing, if
+    /// there are not that many values.
+    pub fn move_to_front_index(&mut self, logical_pos: usize) -> bool {
+        match self.links.index_at(&self.nodes, logical_pos) {
+            Some(index) => {
+                self.links.touch_index(&mut self.nodes, index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the most-recently-used value to the back, making it the next to
+    /// be evicted. Returns `false` if the cache is empty.
+    pub fn demote_front(&mut self) -> bool {
+        match self.links.head {
+            Some(head) => {
+                self.links.demote_index(&mut self.nodes, head);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn lookup<F, R>(&mut self, pred: F) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        self.lookup_with_touch(pred).0
+    }
+
+    /// Like `lookup`, also returning whether the access actually moved the
+    /// matched value, which it does not when the value is already in front.
+    pub fn lookup_with_touch<F, R>(&mut self, mut pred: F) -> (Option<R>, bool)
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        let mut result = None;
+
+        for (i, entry) in self.slots_mut() {
+            if let Some(r) = pred(entry) {
+                result = Some((i, r));
+                break;
+            }
+        }
+
+        self.record_access(result.is_some());
+
+        match result {
+            None => (None, false),
+            Some((i, r)) => {
+                let before = self.links.ahead_of(&self.nodes, i);
+                self.on_access(i);
+                let moved = self.links.ahead_of(&self.nodes, i) != before;
+
+                (Some(r), moved)
+            }
+        }
+    }
+
+    /// Inserts `val` at the front, returning the evicted least-recently-used
+    /// value if the cache was full. A zero-capacity cache stores nothing and
+    /// hands `val` straight back, as does a full one whose admission policy
+    /// rejects `val`. As nothing was stored to make room for, neither case
+    /// fires the eviction hook or counts as an eviction in the stats.
+    pub fn insert(&mut self, val: T) -> Option<T> {
+        if !self.admits(&val) {
+            return Some(val);
+        }
+
+        self.force_insert(val)
+    }
+
+    // Whether `insert` would store `val` rather than hand it straight back
+    fn admits(&mut self, val: &T) -> bool {
+        if C == 0 {
+            return false;
+        }
+
+        #[cfg(feature = "alloc")]
+        if let Some(tail) = self.links.tail.filter(|_| self.is_full()) {
+            if let Some(policy) = &mut self.admission {
+                return policy.admit(val, &self.vals[tail]);
+            }
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        let _ = val;
+
+        true
+    }
+
+    // Inserts `val` like `insert`, bypassing any admission policy
+    fn force_insert(&mut self, val: T) -> Option<T> {
+        if let Some(tail) = self.links.tail.filter(|_| self.is_full()) {
+            #[cfg(feature = "alloc")]
+            if let Some(hook) = &mut self.on_evict {
+                hook(&self.vals[tail]);
+            }
+
+            #[cfg(not(feature = "alloc"))]
+            let _ = tail;
+
+            #[cfg(feature = "stats")]
+            {
+                self.stats.evictions += 1;
+            }
+        }
+
+        self.links.insert(&mut self.vals, &mut self.nodes, C, val)
+    }
+
+    /// Accesses an equal value if one is cached, returning `Ok(false)`,
+    /// otherwise inserts `val` like `insert` and returns `Ok(true)`.
+    ///
+    /// When `insert` would hand `val` straight back, because the cache has
+    /// zero capacity or its admission policy rejects `val`, nothing changes
+    /// and `val` is returned as the error.
+    pub fn insert_unique(&mut self, val: T) -> Result<bool, T>
+    where
+        T: PartialEq,
+    {
+        if self.touch(|x| *x == val) {
+            return Ok(false);
+        }
+
+        if !self.admits(&val) {
+            return Err(val);
+        }
+
+        self.force_insert(val);
+
+        Ok(true)
+    }
+
+    /// Folds `other` into this cache.
+    ///
+    /// The values of `other` are passed to `insert_unique` from least to most
+    /// recently used, so they end up ahead of the values of `self` in their
+    /// own recency order, with the front of `other` at the front. A value
+    /// already present is accessed rather than duplicated. Once full, the
+    /// coldest values are evicted, those of `self` first, and values the
+    /// admission policy rejects are dropped.
+    pub fn merge(&mut self, other: Self)
+    where
+        T: PartialEq,
+    {
+        for val in other.into_iter().rev() {
+            let _ = self.insert_unique(val);
+        }
+    }
+
+    /// Inserts `val` at the front only if there is spare capacity, handing it
+    /// back otherwise. Never evicts.
+    pub fn try_insert(&mut self, val: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(val);
+        }
+
+        self.insert(val);
+
+        Ok(())
+    }
+
+    /// Like `try_insert`, but when full returns the current front value for
+    /// the caller to update instead, say to merge `val` into it, and drops
+    /// `val`. Never evicts.
+    ///
+    /// Fails to compile for a zero-capacity cache, which is always full but
+    /// has no front value.
+    pub fn insert_or_front(&mut self, val: T) -> Result<(), &mut T> {
+        let () = Self::NONZERO_CHECK;
+
+        if !self.is_full() {
+            self.insert(val);
+            return Ok(());
+        }
+
+        Err(self.front_mut().expect("full cache has a front"))
+    }
+
+    /// Like `insert`, but also returns the newly inserted front value.
+    ///
+    /// Fails to compile for a zero-capacity cache, which cannot hold `val`.
+    pub fn insert_get(&mut self, val: T) -> (&mut T, Option<T>) {
+        let () = Self::NONZERO_CHECK;
+
+        let evicted = self.force_insert(val);
+        let head = self.links.head.expect("cache is non-empty after insert");
+
+        (&mut self.vals[head], evicted)
+    }
+
+    /// Inserts every value in order, like `extend`, returning all values
+    /// evicted along the way, oldest eviction first.
+    #[cfg(feature = "alloc")]
+    pub fn insert_many<I>(&mut self, iter: I) -> Vec<T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        iter.into_iter()
+            .filter_map(|val| self.insert(val))
+            .collect()
+    }
+
+    /// Removes and returns the first value matching `pred`.
+    ///
+    /// The freed slot is reclaimed by moving the physically last entry into
+    /// it, so the backing store never has gaps and later inserts can use the
+    /// full capacity.
+    pub fn remove_matching<F>(&mut self, pred: F) -> Option<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let index = self.links.find_index(&self.vals, &self.nodes, pred)?;
+
+        Some(
+            self.links
+                .remove_index(&mut self.vals, &mut self.nodes, index),
+        )
+    }
+
+    /// Moves the first value matching `pred` out of the cache, freeing its
+    /// slot for the next insert.
+    ///
+    /// Unlike `fetch`, this is not an access: the remaining values keep
+    /// their relative recency order and none of them is moved.
+    pub fn take<F>(&mut self, pred: F) -> Option<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.remove_matching(pred)
+    }
+
+    /// Removes every value not matching `pred` in a single pass, keeping the
+    /// recency order of the rest. The removed values are dropped; use
+    /// `extract_if` to get them back.
+    pub fn retain<F>(&mut self, pred: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.links
+            .retain(&mut self.vals, &mut self.nodes, pred, drop)
+    }
+
+    /// Removes the values matching `pred` as the iterator is advanced,
+    /// yielding them from most to least recently used. The rest keep their
+    /// recency order, and values not yet visited when the iterator is
+    /// dropped are kept.
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, C, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        ExtractIf {
+            pos: self.links.head.unwrap_or(0),
+            remaining: self.links.length,
+            vals: &mut self.vals,
+            nodes: &mut self.nodes,
+            links: &mut self.links,
+            pred,
+        }
+    }
+
+    /// Keeps the `keep` most recently used values, removing the rest and
+    /// returning them least recently used first. Changes nothing if there
+    /// are no more than `keep` values.
+    #[cfg(feature = "alloc")]
+    pub fn split_off_lru(&mut self, keep: usize) -> Vec<T> {
+        let mut cold = Vec::with_capacity(self.len().saturating_sub(keep));
+
+        while self.len() > keep {
+            cold.extend(self.pop_lru());
+        }
+
+        cold
+    }
+
+    /// Removes the `n` most recently used values and returns them, most
+    /// recently used first, leaving the rest in order. Removes everything
+    /// if there are no more than `n` values.
+    #[cfg(feature = "alloc")]
+    pub fn drain_n(&mut self, n: usize) -> Vec<T> {
+        let mut hot = Vec::with_capacity(n.min(self.len()));
+
+        while let Some(head) = self.links.head.filter(|_| hot.len() < n) {
+            hot.push(
+                self.links
+                    .remove_index(&mut self.vals, &mut self.nodes, head),
+            );
+        }
+
+        hot
+    }
+
+    /// Removes and returns the least-recently-used value.
+    pub fn pop_lru(&mut self) -> Option<T> {
+        let tail = self.links.tail?;
+
+        Some(
+            self.links
+                .remove_index(&mut self.vals, &mut self.nodes, tail),
+        )
+    }
+
+    pub fn fetch<F>(&mut self, pred: F) -> Option<&mut T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let index = self.access_index(pred)?;
+
+        Some(&mut self.vals[index])
+    }
+
+    /// Like `fetch`, but returns a shared reference to the value.
+    pub fn fetch_ref<F>(&mut self, pred: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let index = self.access_index(pred)?;
+
+        Some(&self.vals[index])
+    }
+
+    /// Returns the first value matching `pred` for in-place mutation, leaving
+    /// its recency unchanged. Unlike `fetch`, this does not count as an
+    /// access and does not move the value to the front.
+    pub fn get_mut_no_touch<F>(&mut self, pred: F) -> Option<&mut T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let index = self.links.find_index(&self.vals, &self.nodes, pred)?;
+
+        Some(&mut self.vals[index])
+    }
+
+    /// Returns mutable references to the first value matching each of
+    /// `preds`, all at once, leaving the recency order unchanged.
+    ///
+    /// Panics if two predicates match the same value.
+    pub fn get_disjoint_mut<F, const N: usize>(&mut self, preds: [F; N]) -> [Option<&mut T>; N]
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let indices = preds.map(|pred| self.links.find_index(&self.vals, &self.nodes, pred));
+
+        for (i, index) in indices.iter().enumerate() {
+            assert!(
+                index.is_none() || !indices[..i].contains(index),
+                "predicates matched the same value"
+            );
+        }
+
+        // Each slot is handed to at most one predicate, so the references are
+        // disjoint without any unsafe code
+        let mut refs = core::array::from_fn(|_| None);
+
+        for (slot, val) in self.vals.iter_mut().enumerate() {
+            if let Some(i) = indices.iter().position(|index| *index == Some(slot)) {
+                refs[i] = Some(val);
+            }
+        }
+
+        refs
+    }
+
+    /// Accesses the first value matching `pred`, or inserts the result of
+    /// `make` at the front if there is none, and returns the value.
+    ///
+    /// Fails to compile for a zero-capacity cache, which cannot hold the
+    /// value.
+    pub fn get_or_insert_with<F, G>(&mut self, pred: F, make: G) -> &mut T
+    where
+        F: FnMut(&T) -> bool,
+        G: FnOnce() -> T,
+    {
+        let () = Self::NONZERO_CHECK;
+
+        let index = match self.access_index(pred) {
+            Some(index) => index,
+            None => {
+                self.force_insert(make());
+                self.links.head.expect("cache is non-empty after insert")
+            }
+        };
+
+        &mut self.vals[index]
+    }
+
+    /// Like `get_or_insert_with`, but inserts the ready-made `val` on a miss.
+    /// On a hit `val` is dropped and the cached value kept. Refuses a
+    /// zero capacity the same way.
+    pub fn access<F>(&mut self, pred: F, val: T) -> &mut T
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.get_or_insert_with(pred, || val)
+    }
+
+    /// Like `get_or_insert_with`, but `make` may fail. On `Err` nothing is
+    /// inserted or evicted and the error is returned as is. Refuses a zero
+    /// capacity the same way.
+    pub fn get_or_try_insert_with<F, G, E>(&mut self, pred: F, make: G) -> Result<&mut T, E>
+    where
+        F: FnMut(&T) -> bool,
+        G: FnOnce() -> Result<T, E>,
+    {
+        let () = Self::NONZERO_CHECK;
+
+        let index = match self.access_index(pred) {
+            Some(index) => index,
+            None => {
+                self.force_insert(make()?);
+                self.links.head.expect("cache is non-empty after insert")
+            }
+        };
+
+        Ok(&mut self.vals[index])
+    }
+
+    /// Searches for the first value matching `pred`, accessing it, and
+    /// returns a handle for updating it or inserting on a miss.
+    pub fn entry<F>(&mut self, pred: F) -> CacheEntry<'_, T, C, P>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let found = self.access_index(pred);
+
+        CacheEntry { cache: self, found }
+    }
 }
@@ -0,0 +1,398 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::DerefMut;
+
+use arrayvec::ArrayVec;
+
+use crate::{Iter, Node, SlotsMut};
+
+// Backing store for one column of the entries of a cache: either the values
+// or their nodes.
+pub(crate) trait Slots<T>: DerefMut<Target = [T]> {
+    fn push(&mut self, item: T);
+
+    fn swap_remove(&mut self, index: usize) -> T;
+}
+
+impl<T, const C: usize> Slots<T> for ArrayVec<T, C> {
+    fn push(&mut self, item: T) {
+        ArrayVec::push(self, item)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        ArrayVec::swap_remove(self, index)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Slots<T> for Vec<T> {
+    fn push(&mut self, item: T) {
+        Vec::push(self, item)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        Vec::swap_remove(self, index)
+    }
+}
+
+// The recency list threaded through a dense slice of nodes, shared by every
+// cache regardless of how its entries are stored.
+//
+// The values and their nodes are kept in two parallel columns, so walking the
+// links only touches the nodes, and each slot index refers to the same entry
+// in both. Entries are kept dense: removing one moves the physically last
+// entry into its slot, so every slot is linked and
+// `length == vals.len() == nodes.len()` always holds. There is never a
+// stranded slot to track, which is why no free list is needed; `insert`
+// pushes whenever `length` is below capacity.
+//
+// The `prev` and `next` links of a node are only meaningful towards its
+// linked neighbours; at either end of the list they hold stale indices.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Links {
+    // Index of the most recently used entry, `None` when empty
+    pub(crate) head: Option<usize>,
+
+    // Index of the least recently used entry, `None` when empty
+    pub(crate) tail: Option<usize>,
+
+    // Number of linked entries
+    pub(crate) length: usize,
+}
+
+impl Links {
+    pub(crate) const fn new() -> Self {
+        Links {
+            head: None,
+            tail: None,
+            length: 0,
+        }
+    }
+
+    // The cursors are only read while `remaining` is non-zero, so any index
+    // will do for an empty list.
+    pub(crate) fn iter<'a, T>(&self, vals: &'a [T], nodes: &'a [Node]) -> Iter<'a, T> {
+        Iter {
+            vals,
+            nodes,
+            pos: self.head.unwrap_or(0),
+            back: self.tail.unwrap_or(0),
+            remaining: self.length,
+        }
+    }
+
+    pub(crate) fn slots_mut<'a, T>(&self, vals: &'a mut [T], nodes: &'a [Node]) -> SlotsMut<'a, T> {
+        SlotsMut {
+            len: vals.len(),
+            vals: vals.as_mut_ptr(),
+            nodes,
+            pos: self.head.unwrap_or(0),
+            back: self.tail.unwrap_or(0),
+            remaining: self.length,
+            marker: PhantomData,
+        }
+    }
+
+    // Links the unlinked entry at `index` in at the front.
+    pub(crate) fn push_front(&mut self, nodes: &mut [Node], index: usize) {
+        match self.head {
+            Some(head) => {
+                nodes[index].next = head;
+                nodes[head].prev = index;
+            }
+            None => self.tail = Some(index),
+        }
+
+        self.head = Some(index);
+        self.length += 1;
+
+        self.debug_check_ends(nodes);
+    }
+
+    // Links the unlinked entry at `index` in at the back.
+    pub(crate) fn push_back(&mut self, nodes: &mut [Node], index: usize) {
+        match self.tail {
+            Some(tail) => {
+                nodes[index].prev = tail;
+                nodes[tail].next = index;
+            }
+            None => self.head = Some(index),
+        }
+
+        self.tail = Some(index);
+        self.length += 1;
+
+        self.debug_check_ends(nodes);
+    }
+
+    // Moves the entry at `index` to the back, making it the next to be
+    // evicted.
+    pub(crate) fn demote_index(&mut self, nodes: &mut [Node], index: usize) {
+        if self.tail != Some(index) {
+            self.remove(nodes, index);
+            self.push_back(nodes, index);
+        }
+    }
+
+    // Unlinks the least recently used entry and returns its index.
+    pub(crate) fn pop_back(&mut self, nodes: &mut [Node]) -> Option<usize> {
+        let tail = self.tail?;
+        self.remove(nodes, tail);
+
+        Some(tail)
+    }
+
+    pub(crate) fn remove(&mut self, nodes: &mut [Node], index: usize) {
+        assert!(self.length > 0);
+
+        let prev = nodes[index].prev;
+        let next = nodes[index].next;
+        let is_head = self.head == Some(index);
+        let is_tail = self.tail == Some(index);
+
+        if is_head {
+            self.head = if is_tail { None } else { Some(next) };
+        } else {
+            nodes[prev].next = next;
+        }
+
+        if is_tail {
+            self.tail = if is_head { None } else { Some(prev) };
+        } else {
+            nodes[next].prev = prev;
+        }
+
+        self.length -= 1;
+
+        self.debug_check_ends(nodes);
+    }
+
+    // Checks in constant time that both ends of the list are where their
+    // neighbours say they are. Compiled out of release builds.
+    #[inline]
+    fn debug_check_ends(&self, nodes: &[Node]) {
+        match (self.head, self.tail) {
+            (None, None) => debug_assert_eq!(self.length, 0, "empty list"),
+            (Some(head), Some(tail)) if self.length == 1 => {
+                debug_assert_eq!(head, tail, "single entry is both ends")
+            }
+            (Some(head), Some(tail)) => {
+                debug_assert_eq!(nodes[nodes[head].next].prev, head, "head linked");
+                debug_assert_eq!(nodes[nodes[tail].prev].next, tail, "tail linked");
+            }
+            _ => debug_assert!(false, "head and tail disagree on emptiness"),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn touch_index(&mut self, nodes: &mut [Node], index: usize) {
+        if self.head != Some(index) {
+            self.remove(nodes, index);
+            self.push_front(nodes, index);
+        }
+    }
+
+    // Returns the slot of the entry just ahead of the one at `index`, which
+    // identifies its position in the list.
+    pub(crate) fn ahead_of(&self, nodes: &[Node], index: usize) -> Option<usize> {
+        if self.head == Some(index) {
+            None
+        } else {
+            Some(nodes[index].prev)
+        }
+    }
+
+    // Swaps the entry at `index` with the one just ahead of it.
+    pub(crate) fn step_up(&mut self, nodes: &mut [Node], index: usize) {
+        if self.head == Some(index) {
+            return;
+        }
+
+        let ahead = nodes[index].prev;
+        self.remove(nodes, index);
+        self.insert_before(nodes, index, ahead);
+    }
+
+    // Links the unlinked entry at `index` in just ahead of the linked entry at
+    // `at`.
+    fn insert_before(&mut self, nodes: &mut [Node], index: usize, at: usize) {
+        if self.head == Some(at) {
+            self.push_front(nodes, index);
+            return;
+        }
+
+        let prev = nodes[at].prev;
+        nodes[prev].next = index;
+        nodes[index].prev = prev;
+        nodes[index].next = at;
+        nodes[at].prev = index;
+
+        self.length += 1;
+
+        self.debug_check_ends(nodes);
+    }
+
+    // Returns the slot index of the entry at recency rank `rank`, 0 being the
+    // front.
+    pub(crate) fn index_at(&self, nodes: &[Node], rank: usize) -> Option<usize> {
+        if rank >= self.length {
+            return None;
+        }
+
+        let mut pos = self.head?;
+
+        for _ in 0..rank {
+            pos = nodes[pos].next;
+        }
+
+        Some(pos)
+    }
+
+    // Returns the slot index of the first entry matching `pred`, walking from
+    // the front.
+    pub(crate) fn find_index<T, F>(&self, vals: &[T], nodes: &[Node], mut pred: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut pos = self.head?;
+
+        for _ in 0..self.length {
+            if pred(&vals[pos]) {
+                return Some(pos);
+            }
+
+            pos = nodes[pos].next;
+        }
+
+        None
+    }
+
+    // Links `val` in at the front, overwriting the least recently used entry
+    // when `capacity` is reached, and returns the overwritten value.
+    pub(crate) fn insert<T, V, N>(
+        &mut self,
+        vals: &mut V,
+        nodes: &mut N,
+        capacity: usize,
+        val: T,
+    ) -> Option<T>
+    where
+        V: Slots<T>,
+        N: Slots<Node>,
+    {
+        // Nothing can be stored, so the value is evicted straight away
+        if capacity == 0 {
+            return Some(val);
+        }
+
+        debug_assert_eq!(self.length, vals.len(), "entries are dense");
+        debug_assert_eq!(vals.len(), nodes.len(), "columns are parallel");
+
+        let victim = if self.length == capacity {
+            self.pop_back(nodes)
+        } else {
+            None
+        };
+
+        let (new_head, evicted) = match victim {
+            Some(last_index) => {
+                // Move the victim out rather than overwriting it in place, so
+                // its destructor runs exactly once, wherever the caller drops
+                // it. Its node is relinked as is.
+                let old = mem::replace(&mut vals[last_index], val);
+
+                (last_index, Some(old))
+            }
+            None => {
+                vals.push(val);
+                nodes.push(Node { prev: 0, next: 0 });
+
+                (vals.len() - 1, None)
+            }
+        };
+
+        self.push_front(nodes, new_head);
+
+        evicted
+    }
+
+    // Unlinks the entry at `index` and moves its value out. The physically
+    // last entry is moved into the vacated slot so both columns stay dense.
+    pub(crate) fn remove_index<T, V, N>(&mut self, vals: &mut V, nodes: &mut N, index: usize) -> T
+    where
+        V: Slots<T>,
+        N: Slots<Node>,
+    {
+        self.remove(nodes, index);
+
+        let last = vals.len() - 1;
+        let val = vals.swap_remove(index);
+        nodes.swap_remove(index);
+
+        if index != last && self.length > 0 {
+            self.relocate(nodes, last, index);
+        }
+
+        debug_assert_eq!(self.length, vals.len(), "entries are dense");
+        debug_assert_eq!(vals.len(), nodes.len(), "columns are parallel");
+
+        val
+    }
+
+    // Points the neighbours of the entry that moved from slot `from` to slot
+    // `to` at its new position.
+    fn relocate(&mut self, nodes: &mut [Node], from: usize, to: usize) {
+        let prev = nodes[to].prev;
+        let next = nodes[to].next;
+
+        if self.head == Some(from) {
+            self.head = Some(to);
+        } else {
+            nodes[prev].next = to;
+        }
+
+        if self.tail == Some(from) {
+            self.tail = Some(to);
+        } else {
+            nodes[next].prev = to;
+        }
+
+        self.debug_check_ends(nodes);
+    }
+
+    // Removes every entry not matching `pred` in a single pass, from the
+    // front, handing each removed value to `removed`.
+    pub(crate) fn retain<T, V, N, F, R>(
+        &mut self,
+        vals: &mut V,
+        nodes: &mut N,
+        mut pred: F,
+        mut removed: R,
+    ) where
+        V: Slots<T>,
+        N: Slots<Node>,
+        F: FnMut(&T) -> bool,
+        R: FnMut(T),
+    {
+        let Some(mut pos) = self.head else {
+            return;
+        };
+
+        for _ in 0..self.length {
+            let next = nodes[pos].next;
+
+            if pred(&vals[pos]) {
+                pos = next;
+                continue;
+            }
+
+            let last = vals.len() - 1;
+            removed(self.remove_index(vals, nodes, pos));
+
+            // The physically last entry was moved into `pos`
+            pos = if next == last { pos } else { next };
+        }
+    }
+}
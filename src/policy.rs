@@ -0,0 +1,74 @@
+use crate::list::Links;
+use crate::Node;
+
+/// Decides how a value moves through the recency order when a lookup
+/// accesses it.
+///
+/// Explicit reordering, such as `touch_matching` or `move_to_front_index`,
+/// always moves values to the front regardless of the policy.
+pub trait AccessPolicy {
+    fn on_access<T>(access: Access<'_, T>);
+}
+
+/// A value that was just accessed, to be repositioned by an `AccessPolicy`.
+pub struct Access<'a, T> {
+    pub(crate) vals: &'a [T],
+    pub(crate) nodes: &'a mut [Node],
+    pub(crate) links: &'a mut Links,
+    pub(crate) index: usize,
+}
+
+impl<T> Access<'_, T> {
+    pub fn value(&self) -> &T {
+        &self.vals[self.index]
+    }
+
+    /// Moves the value to the front.
+    pub fn move_to_front(self) {
+        self.links.touch_index(self.nodes, self.index);
+    }
+
+    /// Swaps the value with the one just ahead of it, if any.
+    pub fn step_up(self) {
+        self.links.step_up(self.nodes, self.index);
+    }
+}
+
+/// Strict LRU: an accessed value moves straight to the front. The default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Lru;
+
+impl AccessPolicy for Lru {
+    fn on_access<T>(access: Access<'_, T>) {
+        access.move_to_front();
+    }
+}
+
+/// An accessed value moves one place towards the front, so a single access
+/// cannot promote a cold value past every hot one.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StepUp;
+
+impl AccessPolicy for StepUp {
+    fn on_access<T>(access: Access<'_, T>) {
+        access.step_up();
+    }
+}
+
+/// Decides whether an insert into a full cache may evict the
+/// least-recently-used value, the victim, to make room for the candidate.
+/// A cache without one admits everything.
+#[cfg(feature = "alloc")]
+pub trait AdmissionPolicy<T> {
+    fn admit(&mut self, candidate: &T, victim: &T) -> bool;
+}
+
+#[cfg(feature = "alloc")]
+impl<T, F> AdmissionPolicy<T> for F
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    fn admit(&mut self, candidate: &T, victim: &T) -> bool {
+        self(candidate, victim)
+    }
+}
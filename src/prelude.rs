@@ -0,0 +1,18 @@
+//! The types and traits needed for typical usage, for glob importing:
+//!
+//! ```
+//! use lru_rs::prelude::*;
+//!
+//! let mut cache = LRUCache::<i32, 2>::new();
+//! cache.insert(1);
+//! ```
+
+#[cfg(feature = "stats")]
+pub use crate::CacheStats;
+pub use crate::{AccessPolicy, Cache, Lru, StepUp};
+#[cfg(feature = "alloc")]
+pub use crate::{AdmissionPolicy, BoxedLRUCache, DynLRUCache, WeightedLRUCache};
+pub use crate::{CacheEntry, Drain, ExtractIf, FrontGuard, IntoIter, Iter, IterMut, LRUCache};
+#[cfg(feature = "std")]
+pub use crate::{Clock, KeyedLRUCache, SyncLRUCache, SystemClock, TtlLRUCache};
+pub use crate::{SampledLRUCache, ScanCache};
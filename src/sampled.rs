@@ -0,0 +1,154 @@
+use core::fmt;
+use core::mem;
+
+use arrayvec::ArrayVec;
+
+// A cached value with the time it was last used
+#[derive(Clone)]
+struct Stamped<T> {
+    val: T,
+    used: u64,
+}
+
+/// An approximate LRU cache: when full, an insert samples two values at
+/// random and evicts the less recently used of the pair, as Redis does.
+///
+/// Accesses only update a counter instead of relinking a list, which makes
+/// them cheaper than in `LRUCache` at the cost of sometimes evicting a value
+/// that is not the least recently used.
+#[derive(Clone)]
+pub struct SampledLRUCache<T, const C: usize> {
+    entries: ArrayVec<Stamped<T>, C>,
+
+    // Logical clock, advanced on every insert and access
+    clock: u64,
+
+    // State of the xorshift generator used for sampling
+    rng: u64,
+}
+
+impl<T, const C: usize> Default for SampledLRUCache<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const C: usize> fmt::Debug for SampledLRUCache<T, C>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const C: usize> SampledLRUCache<T, C> {
+    pub const fn new() -> Self {
+        Self::with_seed(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Creates an empty cache whose sampling is driven by `seed`, for
+    /// reproducible evictions.
+    pub const fn with_seed(seed: u64) -> Self {
+        SampledLRUCache {
+            entries: ArrayVec::new_const(),
+            clock: 0,
+            // Xorshift never leaves the all-zero state
+            rng: if seed == 0 { 1 } else { seed },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub const fn capacity(&self) -> usize {
+        C
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Iterates over the cached values in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|entry| &entry.val)
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    // Picks a pseudo-random index below `bound`, which must be non-zero
+    fn sample(&mut self, bound: usize) -> usize {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+
+        (self.rng % bound as u64) as usize
+    }
+
+    /// Marks the first value matching `pred` as just used.
+    pub fn touch<F>(&mut self, mut pred: F) -> bool
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.lookup(|val| if pred(val) { Some(()) } else { None })
+            .is_some()
+    }
+
+    pub fn lookup<F, R>(&mut self, mut pred: F) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        let now = self.tick();
+
+        self.entries.iter_mut().find_map(|entry| {
+            let r = pred(&entry.val)?;
+            entry.used = now;
+
+            Some(r)
+        })
+    }
+
+    /// Inserts `val`, returning the evicted value if the cache was full. A
+    /// zero-capacity cache stores nothing and hands `val` straight back.
+    pub fn insert(&mut self, val: T) -> Option<T> {
+        if C == 0 {
+            return Some(val);
+        }
+
+        let entry = Stamped {
+            val,
+            used: self.tick(),
+        };
+
+        if !self.entries.is_full() {
+            self.entries.push(entry);
+            return None;
+        }
+
+        let len = self.entries.len();
+        let victim = if len == 1 {
+            0
+        } else {
+            // The second slot is drawn from the other `len - 1`, so the two
+            // are always distinct and the older one really is evicted
+            let first = self.sample(len);
+            let second = (first + 1 + self.sample(len - 1)) % len;
+
+            if self.entries[first].used <= self.entries[second].used {
+                first
+            } else {
+                second
+            }
+        };
+
+        Some(mem::replace(&mut self.entries[victim], entry).val)
+    }
+}
@@ -0,0 +1,107 @@
+use core::fmt;
+
+use arrayvec::ArrayVec;
+
+/// A cache kept as a plain array in recency order, most recently used first.
+///
+/// Accesses shift values along instead of maintaining links, which is cheap
+/// for small capacities, where it beats `LRUCache`.
+#[derive(Clone)]
+pub struct ScanCache<T, const C: usize> {
+    values: ArrayVec<T, C>,
+}
+
+impl<T, const C: usize> Default for ScanCache<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const C: usize> fmt::Debug for ScanCache<T, C>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ScanCache ")?;
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const C: usize> ScanCache<T, C> {
+    pub const fn new() -> Self {
+        ScanCache {
+            values: ArrayVec::new_const(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub const fn capacity(&self) -> usize {
+        C
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Iterates from most to least recently used.
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.values.first()
+    }
+
+    /// Moves the first value matching `pred` to the front.
+    pub fn touch<F>(&mut self, pred: F) -> bool
+    where
+        F: FnMut(&T) -> bool,
+    {
+        match self.values.iter().position(pred) {
+            Some(i) => {
+                self.values[..=i].rotate_right(1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn lookup<F, R>(&mut self, mut pred: F) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        let (i, r) = self
+            .values
+            .iter()
+            .enumerate()
+            .find_map(|(i, val)| Some((i, pred(val)?)))?;
+        self.values[..=i].rotate_right(1);
+
+        Some(r)
+    }
+
+    /// Inserts `val` at the front, returning the evicted least-recently-used
+    /// value if the cache was full. A zero-capacity cache stores nothing and
+    /// hands `val` straight back.
+    pub fn insert(&mut self, val: T) -> Option<T> {
+        if C == 0 {
+            return Some(val);
+        }
+
+        let evicted = if self.values.is_full() {
+            self.values.pop()
+        } else {
+            None
+        };
+        self.values.insert(0, val);
+
+        evicted
+    }
+}
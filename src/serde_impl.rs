@@ -0,0 +1,75 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use arrayvec::ArrayVec;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::{AccessPolicy, LRUCache};
+
+// Caches are serialized as a sequence of values from most to least recently
+// used, so a round-trip reproduces the same recency order.
+impl<T, const C: usize, P: AccessPolicy> Serialize for LRUCache<T, C, P>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+
+        for val in self.iter() {
+            seq.serialize_element(val)?;
+        }
+
+        seq.end()
+    }
+}
+
+impl<'de, T, const C: usize, P: AccessPolicy> Deserialize<'de> for LRUCache<T, C, P>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(CacheVisitor(PhantomData))
+    }
+}
+
+struct CacheVisitor<T, const C: usize, P>(PhantomData<(T, P)>);
+
+impl<'de, T, const C: usize, P: AccessPolicy> Visitor<'de> for CacheVisitor<T, C, P>
+where
+    T: Deserialize<'de>,
+{
+    type Value = LRUCache<T, C, P>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of at most {} values", C)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = ArrayVec::<T, C>::new();
+
+        while let Some(val) = seq.next_element()? {
+            if values.try_push(val).is_err() {
+                return Err(de::Error::invalid_length(C + 1, &self));
+            }
+        }
+
+        // Insert from least to most recently used to rebuild the links
+        let mut cache = LRUCache::default();
+
+        while let Some(val) = values.pop() {
+            cache.insert(val);
+        }
+
+        Ok(cache)
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::LRUCache;
+
+/// An `LRUCache` behind a mutex, shareable between threads.
+///
+/// Every method locks internally and hands back owned values, so no guard
+/// escapes. The wrapper is `Send + Sync` whenever `T: Send`.
+pub struct SyncLRUCache<T, const C: usize> {
+    cache: Mutex<LRUCache<T, C>>,
+}
+
+impl<T, const C: usize> Default for SyncLRUCache<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const C: usize> SyncLRUCache<T, C> {
+    pub const fn new() -> Self {
+        SyncLRUCache {
+            cache: Mutex::new(LRUCache::new()),
+        }
+    }
+
+    // No operation leaves the list half-linked, so a panic in a caller's
+    // predicate does not make the cache unusable for other threads.
+    fn lock(&self) -> MutexGuard<'_, LRUCache<T, C>> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub const fn capacity(&self) -> usize {
+        C
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    /// Inserts `val` at the front, returning the evicted least-recently-used
+    /// value if the cache was full.
+    pub fn insert(&self, val: T) -> Option<T> {
+        self.lock().insert(val)
+    }
+
+    /// Returns a clone of the first value matching `pred`, moving it to the
+    /// front.
+    pub fn get<F>(&self, pred: F) -> Option<T>
+    where
+        F: FnMut(&T) -> bool,
+        T: Clone,
+    {
+        self.lock().fetch(pred).map(|x| x.clone())
+    }
+
+    pub fn lookup<F, R>(&self, pred: F) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        self.lock().lookup(pred)
+    }
+
+    pub fn into_inner(self) -> LRUCache<T, C> {
+        self.cache
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,179 @@
+use core::fmt;
+use std::time::{Duration, Instant};
+
+use crate::LRUCache;
+
+/// Source of the current time for a `TtlLRUCache`.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The real clock, backed by `Instant::now`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// An LRU cache whose values also expire a fixed time after insertion,
+/// regardless of how recently they were used.
+///
+/// Expired values are never returned. They are dropped lazily, when a lookup
+/// walks past them or an insert needs the slot of an expired least recently
+/// used value, and all at once by `purge_expired`.
+pub struct TtlLRUCache<T, const C: usize, K = SystemClock> {
+    // Each value is stored with its deadline, `None` meaning it never expires
+    cache: LRUCache<(T, Option<Instant>), C>,
+    clock: K,
+}
+
+impl<T, const C: usize> Default for TtlLRUCache<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const C: usize, K> fmt::Debug for TtlLRUCache<T, C, K>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TtlLRUCache")
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+impl<T, const C: usize> TtlLRUCache<T, C> {
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl<T, const C: usize, K> TtlLRUCache<T, C, K>
+where
+    K: Clock,
+{
+    pub fn with_clock(clock: K) -> Self {
+        TtlLRUCache {
+            cache: LRUCache::new(),
+            clock,
+        }
+    }
+
+    /// Number of cached values, including expired ones not yet purged.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Inserts `val` at the front, to expire once `ttl` has passed.
+    ///
+    /// When the cache is full and its least recently used value has expired,
+    /// that value makes room instead of being returned as evicted.
+    pub fn insert(&mut self, val: T, ttl: Duration) -> Option<T> {
+        let now = self.clock.now();
+        let cache = &mut self.cache;
+
+        if let Some(tail) = cache
+            .links
+            .tail
+            .filter(|&tail| cache.is_full() && !is_live(&cache.vals[tail], now))
+        {
+            cache
+                .links
+                .remove_index(&mut cache.vals, &mut cache.nodes, tail);
+        }
+
+        let deadline = now.checked_add(ttl);
+
+        cache.insert((val, deadline)).map(|(val, _)| val)
+    }
+
+    /// Like `LRUCache::lookup`, skipping expired values.
+    pub fn lookup<F, R>(&mut self, pred: F) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        let found = self.find_live(pred);
+        self.cache.record_access(found.is_some());
+
+        found.map(|(index, r)| {
+            self.cache.on_access(index);
+            r
+        })
+    }
+
+    /// Returns the first live value matching `pred`, moving it to the front.
+    pub fn get<F>(&mut self, mut pred: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let found = self.find_live(|val| pred(val).then_some(()));
+        self.cache.record_access(found.is_some());
+
+        let (index, ()) = found?;
+        self.cache.on_access(index);
+
+        Some(&self.cache.vals[index].0)
+    }
+
+    // Walks from the front for the first live value for which `pred` returns
+    // `Some`, dropping the expired values passed over on the way, and returns
+    // its slot with the result. Values past the match are left alone.
+    fn find_live<F, R>(&mut self, mut pred: F) -> Option<(usize, R)>
+    where
+        F: FnMut(&T) -> Option<R>,
+    {
+        let now = self.clock.now();
+        let cache = &mut self.cache;
+        let mut pos = cache.links.head?;
+
+        for _ in 0..cache.links.length {
+            let next = cache.nodes[pos].next;
+
+            if is_live(&cache.vals[pos], now) {
+                if let Some(r) = pred(&cache.vals[pos].0) {
+                    return Some((pos, r));
+                }
+
+                pos = next;
+                continue;
+            }
+
+            let last = cache.vals.len() - 1;
+            cache
+                .links
+                .remove_index(&mut cache.vals, &mut cache.nodes, pos);
+
+            // The physically last entry was moved into `pos`
+            pos = if next == last { pos } else { next };
+        }
+
+        None
+    }
+
+    /// Removes every expired value.
+    pub fn purge_expired(&mut self) {
+        let now = self.clock.now();
+        let cache = &mut self.cache;
+
+        // Straight to the links, so no `Vec` of expired values is built
+        cache.links.retain(
+            &mut cache.vals,
+            &mut cache.nodes,
+            |entry| is_live(entry, now),
+            drop,
+        );
+    }
+}
+
+fn is_live<T>((_, deadline): &(T, Option<Instant>), now: Instant) -> bool {
+    deadline.is_none_or(|d| d > now)
+}
@@ -0,0 +1,133 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::list::Links;
+use crate::Node;
+
+/// An LRU cache bounded by the total weight of its values rather than their
+/// number.
+pub struct WeightedLRUCache<T> {
+    // Each value is stored with its weight
+    vals: Vec<(T, usize)>,
+    nodes: Vec<Node>,
+    links: Links,
+    weight: usize,
+    max_weight: usize,
+}
+
+impl<T> fmt::Debug for WeightedLRUCache<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeightedLRUCache")
+            .field("weight", &self.weight)
+            .field("max_weight", &self.max_weight)
+            .field("vals", &self.vals)
+            .field("nodes", &self.nodes)
+            .finish()
+    }
+}
+
+impl<T> WeightedLRUCache<T> {
+    pub fn with_max_weight(max_weight: usize) -> Self {
+        WeightedLRUCache {
+            vals: Vec::new(),
+            nodes: Vec::new(),
+            links: Links::new(),
+            weight: 0,
+            max_weight,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.length == 0
+    }
+
+    /// Total weight of the cached values.
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    pub fn max_weight(&self) -> usize {
+        self.max_weight
+    }
+
+    pub fn clear(&mut self) {
+        self.vals.clear();
+        self.nodes.clear();
+        self.links = Links::new();
+        self.weight = 0;
+    }
+
+    pub fn items(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.links
+            .iter(&self.vals, &self.nodes)
+            .map(|(val, _)| val.clone())
+            .collect()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        let head = self.links.head?;
+
+        Some(&self.vals[head].0)
+    }
+
+    pub fn touch<F>(&mut self, mut pred: F) -> bool
+    where
+        F: FnMut(&T) -> bool,
+    {
+        match self
+            .links
+            .find_index(&self.vals, &self.nodes, |(val, _)| pred(val))
+        {
+            Some(i) => {
+                self.links.touch_index(&mut self.nodes, i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts `val` at the front, first evicting least-recently-used values
+    /// until `weight` fits in the budget, and returns the evicted values in
+    /// eviction order.
+    ///
+    /// A value heavier than the whole budget is not inserted; it is returned
+    /// on its own and nothing else is evicted.
+    pub fn insert(&mut self, val: T, weight: usize) -> Vec<T> {
+        if weight > self.max_weight {
+            return vec![val];
+        }
+
+        let mut evicted = Vec::new();
+
+        while let Some(tail) = self
+            .links
+            .tail
+            .filter(|_| weight > self.max_weight - self.weight)
+        {
+            let (old, old_weight) = self
+                .links
+                .remove_index(&mut self.vals, &mut self.nodes, tail);
+
+            self.weight -= old_weight;
+            evicted.push(old);
+        }
+
+        // The weight budget is the only bound on the number of entries
+        self.links
+            .insert(&mut self.vals, &mut self.nodes, usize::MAX, (val, weight));
+        self.weight += weight;
+
+        evicted
+    }
+}
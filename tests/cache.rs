@@ -0,0 +1,69 @@
+#![cfg(feature = "alloc")]
+
+use lru_rs::{with_capacity_hint, Cache, DynLRUCache, LRUCache, ScanCache};
+
+// Exercises a cache of capacity 3 only through the trait
+fn check(cache: &mut dyn Cache<i32>) {
+    assert!(cache.is_empty());
+    assert_eq!(cache.capacity(), 3);
+
+    assert_eq!(cache.insert(1), None);
+    assert_eq!(cache.insert(2), None);
+    assert_eq!(cache.insert(3), None);
+    assert_eq!(cache.front(), Some(&3));
+
+    assert!(cache.touch(&mut |x| *x == 1));
+    assert_eq!(cache.get(&mut |x| *x == 2), Some(&2));
+    assert_eq!(cache.get(&mut |x| *x == 9), None, "Cache miss.");
+    assert_eq!(cache.front(), Some(&2));
+
+    assert_eq!(cache.insert(4), Some(3), "LRU value evicted.");
+    assert_eq!(cache.len(), 3);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_implementations() {
+    let mut caches: Vec<Box<dyn Cache<i32>>> = vec![
+        Box::new(LRUCache::<i32, 3>::new()),
+        Box::new(DynLRUCache::with_capacity(3)),
+        Box::new(ScanCache::<i32, 3>::new()),
+        with_capacity_hint::<i32, 3>(),
+    ];
+
+    for cache in &mut caches {
+        check(cache.as_mut());
+    }
+}
+
+#[test]
+fn test_capacity_hint() {
+    for mut cache in [
+        with_capacity_hint::<i32, 4>(),
+        with_capacity_hint::<i32, 64>(),
+    ] {
+        for i in 0..100 {
+            cache.insert(i);
+        }
+
+        assert_eq!(cache.len(), cache.capacity());
+        assert_eq!(cache.front(), Some(&99));
+    }
+}
+
+#[test]
+fn test_scan_lookup() {
+    let mut cache = ScanCache::<i32, 4>::new();
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+
+    assert_eq!(
+        cache.lookup(|x| if *x == 1 { Some(x * 10) } else { None }),
+        Some(10)
+    );
+    assert_eq!(cache.iter().copied().collect::<Vec<_>>(), [1, 3, 2]);
+    assert_eq!(format!("{:?}", cache), "ScanCache [1, 3, 2]");
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "alloc")]
+
+use lru_rs::DynLRUCache;
+
+#[test]
+fn test_insert() {
+    let capacity = "3".parse().unwrap();
+    let mut cache = DynLRUCache::with_capacity(capacity);
+    assert_eq!(cache.capacity(), 3);
+    assert!(cache.is_empty());
+
+    assert_eq!(cache.insert(1), None);
+    assert_eq!(cache.insert(2), None);
+    assert_eq!(cache.insert(3), None);
+    assert_eq!(
+        cache.items(),
+        [3, 2, 1],
+        "Ordered from most to least recent"
+    );
+
+    assert_eq!(cache.insert(4), Some(1), "Least-recently-used item evicted");
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.items(), [4, 3, 2]);
+}
+
+#[test]
+fn test_lookup_touch() {
+    let mut cache = DynLRUCache::with_capacity(3);
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+
+    assert!(cache.touch(|x| *x == 1));
+    assert_eq!(cache.items(), [1, 3, 2], "Touched item is moved to front.");
+
+    let result = cache.lookup(|x| if *x == 2 { Some(*x * 2) } else { None });
+    assert_eq!(result, Some(4), "Cache hit.");
+    assert_eq!(cache.items(), [2, 1, 3], "Matching item moved to front.");
+
+    assert_eq!(cache.insert(4), Some(3));
+    assert_eq!(cache.front(), Some(&4));
+}
+
+#[test]
+fn test_resize() {
+    let mut cache = DynLRUCache::with_capacity(4);
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+
+    assert_eq!(cache.resize(6), [], "Growing evicts nothing.");
+    cache.insert(5);
+    cache.insert(6);
+    assert_eq!(cache.items(), [6, 5, 4, 3, 2, 1], "Room for more entries.");
+
+    assert_eq!(cache.resize(6), [], "Same length evicts nothing.");
+
+    cache.touch(|x| *x == 2);
+    assert_eq!(cache.resize(3), [1, 3, 4], "Evicted from the back.");
+    assert_eq!(cache.items(), [2, 6, 5], "Survivors keep their order.");
+    assert_eq!(cache.capacity(), 3);
+    assert_eq!(cache.insert(7), Some(5), "New capacity is enforced.");
+
+    assert_eq!(cache.items(), [7, 2, 6]);
+
+    assert_eq!(
+        cache.resize(0),
+        [6, 2, 7],
+        "Shrinking to 0 evicts everything."
+    );
+    assert!(cache.is_empty());
+    assert_eq!(cache.insert(8), Some(8), "Nothing fits at capacity 0.");
+    assert_eq!(cache.items(), []);
+}
+
+#[test]
+fn test_shrink_to() {
+    let mut cache = DynLRUCache::with_capacity(4);
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+    let allocated = cache.allocated_capacity();
+
+    assert_eq!(cache.shrink_to(4), [], "Nothing to evict.");
+    assert_eq!(cache.shrink_to(1), [1, 2, 3], "Evicted from the back.");
+    assert_eq!(cache.items(), [4], "Most recently used kept.");
+
+    assert_eq!(cache.capacity(), 4, "Capacity unchanged.");
+    assert_eq!(cache.allocated_capacity(), allocated, "Allocation kept.");
+
+    cache.insert(5);
+    cache.insert(6);
+    cache.insert(7);
+    assert_eq!(cache.insert(8), Some(4), "Fills up to the old capacity.");
+}
+
+#[test]
+fn test_reserve() {
+    let mut cache = DynLRUCache::with_capacity(2);
+    cache.reserve(10);
+
+    assert_eq!(cache.capacity(), 2, "Logical capacity unchanged.");
+    assert!(cache.allocated_capacity() >= 10, "Backing storage grown.");
+
+    cache.insert(1);
+    cache.insert(2);
+    assert_eq!(cache.insert(3), Some(1), "Still evicts at capacity.");
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Capacity overflow")]
+fn test_absurd_capacity() {
+    DynLRUCache::<i32>::with_capacity(usize::MAX);
+}
+
+#[test]
+#[should_panic(expected = "Capacity overflow")]
+fn test_absurd_resize() {
+    DynLRUCache::<i32>::with_capacity(4).resize(isize::MAX as usize + 1);
+}
+
+#[test]
+fn test_large_capacity_allowed() {
+    // Nothing is allocated up front, so this is fine
+    let mut cache = DynLRUCache::with_capacity(isize::MAX as usize);
+    assert_eq!(cache.insert(1), None);
+}
+
+#[test]
+fn test_clear_and_shrink() {
+    let mut cache = DynLRUCache::with_capacity(100);
+    for i in 0..100 {
+        cache.insert(i);
+    }
+
+    cache.clear_and_shrink();
+    assert!(cache.is_empty());
+    assert_eq!(cache.allocated_capacity(), 0, "Allocation released.");
+    assert_eq!(cache.capacity(), 100, "Logical capacity unchanged.");
+
+    cache.insert(1);
+    cache.insert(2);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.items(), [2, 1]);
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "std")]
+
+use lru_rs::KeyedLRUCache;
+
+type TestCache = KeyedLRUCache<String, i32, 3>;
+
+#[test]
+fn test_put_get() {
+    let mut cache = TestCache::default();
+
+    assert_eq!(cache.put("a".to_string(), 1), None);
+    assert_eq!(cache.put("b".to_string(), 2), None);
+    assert_eq!(cache.len(), 2);
+
+    assert_eq!(cache.get(&"a".to_string()), Some(&1));
+    assert_eq!(cache.get(&"b".to_string()), Some(&2));
+    assert_eq!(cache.get(&"c".to_string()), None, "Cache miss.");
+}
+
+#[test]
+fn test_put_existing_key() {
+    let mut cache = TestCache::default();
+
+    cache.put("a".to_string(), 1);
+    assert_eq!(
+        cache.put("a".to_string(), 10),
+        Some(1),
+        "Previous value returned."
+    );
+    assert_eq!(cache.len(), 1, "Key not duplicated.");
+    assert_eq!(cache.get(&"a".to_string()), Some(&10));
+}
+
+#[test]
+fn test_put_evicts() {
+    let mut cache = TestCache::default();
+
+    cache.put("a".to_string(), 1);
+    cache.put("b".to_string(), 2);
+    cache.put("c".to_string(), 3);
+
+    // Make "a" the most recently used, so "b" is next to go
+    cache.get(&"a".to_string());
+
+    assert_eq!(cache.put("d".to_string(), 4), Some(2), "LRU value evicted.");
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get(&"b".to_string()), None, "Evicted key removed.");
+    assert_eq!(cache.get(&"a".to_string()), Some(&1));
+    assert_eq!(cache.get(&"d".to_string()), Some(&4));
+}
+
+#[test]
+fn test_get_or_insert_with() {
+    let mut cache = TestCache::default();
+    cache.put("a".to_string(), 1);
+
+    let mut calls = 0;
+    let mut compute = |val| {
+        calls += 1;
+        val
+    };
+
+    assert_eq!(
+        cache.get_or_insert_with("a".to_string(), || compute(10)),
+        &1
+    );
+    assert_eq!(cache.get_or_insert_with("b".to_string(), || compute(2)), &2);
+    assert_eq!(cache.get_or_insert_with("c".to_string(), || compute(3)), &3);
+    assert_eq!(calls, 2, "Only computed on a miss.");
+
+    // "a" was touched first, so "a" is the least recently used
+    assert_eq!(cache.get_or_insert_with("d".to_string(), || 4), &4);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get(&"a".to_string()), None, "Evicted key removed.");
+    assert_eq!(
+        cache.put("a".to_string(), 5),
+        Some(2),
+        "Re-inserting the evicted key evicts the next LRU, not a stale entry."
+    );
+    assert_eq!(cache.get(&"c".to_string()), Some(&3));
+    assert_eq!(cache.get(&"d".to_string()), Some(&4));
+}
+
+#[test]
+fn test_extend() {
+    let mut cache = TestCache::default();
+    cache.put("a".to_string(), 1);
+
+    cache.extend([("b", 2), ("a", 10), ("c", 3)].map(|(k, v)| (k.to_string(), v)));
+    assert_eq!(cache.len(), 3, "Repeated key not duplicated.");
+    assert_eq!(cache.get(&"a".to_string()), Some(&10), "Updated in place.");
+
+    // "a" was just touched, so "b" is the least recently used
+    cache.extend([("d".to_string(), 4)]);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get(&"b".to_string()), None, "LRU key evicted.");
+    assert_eq!(cache.get(&"c".to_string()), Some(&3));
+    assert_eq!(cache.get(&"d".to_string()), Some(&4));
+}
+
+#[test]
+fn test_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<TestCache>();
+}
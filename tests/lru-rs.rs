@@ -1,3 +1,10 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::Cell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
 use lru_rs::LRUCache;
 
 extern crate quickcheck;
@@ -7,14 +14,71 @@ extern crate quickcheck_macros;
 
 type TestCache = LRUCache<i32, 4>;
 
+// Counts how many times values sharing the counter have been dropped
+struct DropCounter(Rc<Cell<usize>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
 #[test]
 fn test_empty() {
-    let mut cache = TestCache::default();
+    let cache = TestCache::default();
 
     assert_eq!(cache.len(), 0);
     assert_eq!(cache.items(), []);
 }
 
+#[test]
+fn test_new() {
+    const EMPTY: TestCache = TestCache::new();
+    static SHARED: TestCache = LRUCache::new();
+
+    let mut cache = EMPTY;
+    assert!(cache.is_empty());
+    assert!(SHARED.is_empty());
+
+    cache.insert(1);
+    assert_eq!(cache.items(), [1]);
+    assert_eq!(TestCache::default(), TestCache::new(), "Default is empty.");
+}
+
+#[test]
+fn test_capacity() {
+    let mut cache = TestCache::default();
+    assert_eq!(cache.capacity(), 4);
+
+    cache.insert(1);
+    assert_eq!(cache.capacity(), 4, "Capacity is independent of length.");
+}
+
+#[test]
+fn test_capacity_const() {
+    const CAPACITY: usize = TestCache::CAPACITY;
+    let scratch = [0u8; LRUCache::<String, 6>::CAPACITY];
+
+    assert_eq!(CAPACITY, 4);
+    assert_eq!(scratch.len(), 6, "Usable as an array length.");
+    assert_eq!(TestCache::CAPACITY, TestCache::new().capacity());
+}
+
+#[test]
+fn test_is_full() {
+    let mut cache = TestCache::default();
+    assert!(!cache.is_full());
+
+    cache.extend(1..=3);
+    assert!(!cache.is_full(), "Room for one more.");
+
+    cache.insert(4);
+    assert!(cache.is_full());
+
+    cache.pop_lru();
+    assert!(!cache.is_full());
+}
+
 #[test]
 fn test_insert() {
     let mut cache = TestCache::default();
@@ -60,126 +124,1729 @@ fn test_insert() {
 }
 
 #[test]
-fn test_lookup() {
+fn test_insert_evicted() {
+    let mut cache = TestCache::default();
+
+    assert_eq!(cache.insert(1), None);
+    assert_eq!(cache.insert(2), None);
+    assert_eq!(cache.insert(3), None);
+    assert_eq!(cache.insert(4), None, "Spare capacity, nothing evicted.");
+
+    assert_eq!(cache.insert(5), Some(1), "Least recently used is evicted.");
+    assert_eq!(cache.items(), [5, 4, 3, 2]);
+
+    cache.touch(|x| *x == 2);
+    assert_eq!(cache.insert(6), Some(3), "Touched item is not evicted.");
+}
+
+#[test]
+fn test_try_insert() {
     let mut cache = TestCache::default();
+
+    assert_eq!(cache.try_insert(1), Ok(()));
+    assert_eq!(cache.try_insert(2), Ok(()));
+    assert_eq!(cache.try_insert(3), Ok(()));
+    assert_eq!(cache.try_insert(4), Ok(()));
+
+    assert_eq!(cache.try_insert(5), Err(5), "Value handed back when full.");
+    assert_eq!(cache.items(), [4, 3, 2, 1], "Nothing evicted.");
+}
+
+#[test]
+fn test_eviction_hook() {
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let seen = evicted.clone();
+    let mut cache = LRUCache::<i32, 2>::with_eviction_hook(move |x| seen.lock().unwrap().push(*x));
+
     cache.insert(1);
     cache.insert(2);
-    cache.insert(3);
+    assert_eq!(*evicted.lock().unwrap(), [], "Nothing evicted yet.");
+
+    assert_eq!(cache.insert(3), Some(1), "Evicted value still returned.");
+    cache.touch(|x| *x == 2);
     cache.insert(4);
+    assert_eq!(*evicted.lock().unwrap(), [1, 3], "Evicted in order.");
 
-    let result = cache.lookup(|x| if *x == 5 { Some(()) } else { None });
-    assert_eq!(result, None, "Cache miss.");
-    assert_eq!(cache.items(), [4, 3, 2, 1], "Order not changed.");
+    cache.remove_matching(|x| *x == 4);
+    cache.pop_lru();
+    cache.insert(5);
+    cache.clear();
+    assert_eq!(
+        *evicted.lock().unwrap(),
+        [1, 3],
+        "Only capacity-driven evictions are reported."
+    );
+}
 
-    // Cache hit
-    let result = cache.lookup(|x| if *x == 3 { Some(*x * 2) } else { None });
-    assert_eq!(result, Some(6), "Cache hit.");
-    assert_eq!(cache.items(), [3, 4, 2, 1], "Matching item moved to front.");
+#[test]
+fn test_admission_policy() {
+    let mut cache = LRUCache::<i32, 2>::with_admission_policy(|candidate: &i32, victim: &i32| {
+        candidate >= victim
+    });
+
+    cache.insert(5);
+    cache.insert(3);
+    assert_eq!(cache.insert(1), Some(1), "Smaller candidate rejected.");
+    assert_eq!(cache.items(), [3, 5], "Victim kept, order unchanged.");
+
+    assert_eq!(cache.insert(7), Some(5), "Larger candidate evicts the LRU.");
+    assert_eq!(cache.items(), [7, 3]);
+
+    let forced = cache.get_or_insert_with(|x| *x == 0, || 0);
+    assert_eq!(*forced, 0, "Returning inserts always admit.");
+    assert_eq!(cache.items(), [0, 7]);
 }
 
 #[test]
-fn test_clear() {
+fn test_insert_unique() {
     let mut cache = TestCache::default();
-    cache.insert(1);
-    cache.clear();
 
-    assert_eq!(cache.len(), 0);
-    assert_eq!(cache.items(), [], "All items evicted");
+    assert_eq!(cache.insert_unique(1), Ok(true));
+    assert_eq!(cache.insert_unique(2), Ok(true));
+    assert_eq!(cache.insert_unique(2), Ok(false), "Duplicate not inserted.");
+    assert_eq!(cache.items(), [2, 1]);
+    assert_eq!(cache.len(), 2);
+
+    assert_eq!(cache.insert_unique(1), Ok(false));
+    assert_eq!(cache.items(), [1, 2], "Existing value moved to front.");
+}
+
+#[test]
+fn test_insert_unique_rejected() {
+    let mut cache = LRUCache::<i32, 2>::with_admission_policy(|candidate: &i32, victim: &i32| {
+        candidate >= victim
+    });
+    cache.extend([5, 3]);
+
+    assert_eq!(
+        cache.insert_unique(1),
+        Err(1),
+        "Rejected value handed back."
+    );
+    assert_eq!(cache.items(), [3, 5]);
+    assert_eq!(cache.insert_unique(7), Ok(true));
+    assert_eq!(cache.items(), [7, 3]);
+
+    assert_eq!(LRUCache::<i32, 0>::new().insert_unique(1), Err(1));
+}
+
+#[test]
+fn test_insert_owned() {
+    let mut cache = LRUCache::<String, 2>::default();
+
+    cache.insert("a".to_string());
+    cache.insert("b".to_string());
+    cache.insert("c".to_string());
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.items(), ["c", "b"], "Non-Copy values are evicted.");
+    assert_eq!(
+        cache.fetch(|x| x == "b").map(|x| x.as_str()),
+        Some("b"),
+        "Non-Copy values can be fetched."
+    );
+}
+
+#[test]
+fn test_items_clone() {
+    let mut cache = LRUCache::<String, 4>::default();
+    cache.extend(["a", "b", "c"].map(String::from));
+
+    let mut items = cache.items();
+    assert_eq!(items, ["c", "b", "a"], "Values cloned out in order.");
+
+    items[0].push('!');
+    assert_eq!(
+        cache.front().map(String::as_str),
+        Some("c"),
+        "Cache keeps its own copies."
+    );
+}
+
+#[test]
+fn test_iter() {
+    let mut cache = TestCache::default();
+    assert_eq!(cache.iter().next(), None, "Empty cache yields nothing.");
 
     cache.insert(1);
     cache.insert(2);
     cache.insert(3);
     cache.insert(4);
-    assert_eq!(cache.items(), [4, 3, 2, 1]);
-    cache.clear();
-    assert_eq!(cache.items(), [], "All items evicted again");
-}
+    cache.insert(5);
+    cache.touch(|x| *x == 3);
 
-#[quickcheck]
-fn test_touch(num: i16) {
-    let first: i32 = num.into();
-    let second = first + 1;
-    let third = first + 2;
-    let fourth = first + 3;
+    assert_eq!(
+        cache.iter().copied().collect::<Vec<_>>(),
+        [3, 5, 4, 2],
+        "Ordered from most to least recent"
+    );
+    assert_eq!(cache.iter().len(), 4);
+    assert_eq!(cache.iter().filter(|x| **x % 2 == 0).count(), 2);
+}
 
+#[test]
+fn test_iter_rev() {
     let mut cache = TestCache::default();
+    assert_eq!(cache.iter().next_back(), None);
 
-    cache.insert(first);
-    cache.insert(second);
-    cache.insert(third);
-    cache.insert(fourth);
+    cache.extend(1..=6);
+    cache.touch(|x| *x == 4);
 
-    cache.touch(|x| *x == fourth + 1);
+    let mut reversed = cache.items();
+    reversed.reverse();
+    assert_eq!(cache.iter().rev().copied().collect::<Vec<_>>(), reversed);
+
+    let mut iter = cache.iter();
+    assert_eq!(iter.next(), Some(&4));
+    assert_eq!(iter.next_back(), Some(&3));
+    assert_eq!(iter.next_back(), Some(&5));
+    assert_eq!(iter.next(), Some(&6));
+    assert_eq!(iter.next(), None, "Cursors meet in the middle.");
+    assert_eq!(iter.next_back(), None);
+
+    assert_eq!(cache.into_iter().rev().collect::<Vec<_>>(), reversed);
+}
+
+#[test]
+fn test_iter_mut() {
+    let mut cache = TestCache::default();
+    cache.extend(1..=5);
+    cache.touch(|x| *x == 3);
 
+    for (val, bump) in cache.iter_mut().zip([100, 200, 300, 400]) {
+        *val += bump;
+    }
     assert_eq!(
         cache.items(),
-        [fourth, third, second, first],
-        "Nothing is touched."
+        [103, 205, 304, 402],
+        "Updated in recency order."
     );
 
-    cache.touch(|x| *x == second);
+    let refs: Vec<&mut i32> = cache.iter_mut().rev().collect();
+    assert_eq!(refs.len(), 4, "References can be held together.");
+    for val in refs {
+        *val = -*val;
+    }
 
+    for val in &mut cache {
+        *val += 1;
+    }
     assert_eq!(
         cache.items(),
-        [second, fourth, third, first],
-        "Touched item is moved to front."
+        [-102, -204, -303, -401],
+        "Order not changed."
     );
 }
 
-#[quickcheck]
-fn test_fetch(num: i16) {
-    let first: i32 = num.into();
-    let second = first + 1;
-    let third = first + 2;
-    let fourth = first + 3;
+#[test]
+fn test_into_iter() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+    cache.insert(5);
+    cache.touch(|x| *x == 3);
+
+    let borrowed: Vec<i32> = (&cache).into_iter().copied().collect();
+    assert_eq!(borrowed, [3, 5, 4, 2]);
+
+    let owned: Vec<i32> = cache.into_iter().collect();
+    assert_eq!(owned, [3, 5, 4, 2], "Values moved out in recency order.");
 
+    let mut cache = LRUCache::<String, 3>::default();
+    cache.insert("a".to_string());
+    cache.insert("b".to_string());
+    cache.touch(|x| x == "a");
+
+    let mut values = Vec::new();
+    for v in cache {
+        values.push(v);
+    }
+    assert_eq!(values, ["a", "b"]);
+}
+
+#[test]
+fn test_items_shared() {
     let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
 
-    cache.insert(first);
-    cache.insert(second);
-    cache.insert(third);
-    cache.insert(fourth);
+    let shared = &cache;
+    assert_eq!(shared.items(), [2, 1], "Snapshot behind a shared borrow.");
+}
 
-    cache.fetch(|x| *x == fourth + 1);
+#[test]
+fn test_clone_from() {
+    let mut source = LRUCache::<String, 4>::default();
+    source.extend(["a", "b", "c", "d", "e"].map(String::from));
+    source.touch(|x| x == "c");
 
-    assert_eq!(
-        cache.items(),
-        [fourth, third, second, first],
-        "Nothing is touched."
-    );
+    let mut target = LRUCache::<String, 4>::default();
+    target.extend(["x", "y"].map(String::from));
 
-    cache.fetch(|x| *x == second);
+    target.clone_from(&source);
+    assert_eq!(target, source, "Same values in the same order.");
+    assert_eq!(target.items(), ["c", "e", "d", "b"]);
+
+    source.clear();
+    target.clone_from(&source);
+    assert!(target.is_empty());
+}
+
+#[test]
+fn test_from_iter() {
+    let cache: TestCache = (1..=6).collect();
+    assert_eq!(cache.items(), [6, 5, 4, 3], "Only the last items survive.");
+
+    let mut cache: TestCache = vec![1, 2].into_iter().collect();
+    assert_eq!(cache.items(), [2, 1]);
 
+    cache.extend([3, 4, 5]);
     assert_eq!(
         cache.items(),
-        [second, fourth, third, first],
-        "Fetched item is moved to front."
+        [5, 4, 3, 2],
+        "Extends like repeated inserts."
     );
 }
 
-#[quickcheck]
-fn test_front(num: i16) {
-    let first: i32 = num.into();
-    let second = first + 1;
+#[test]
+fn test_insert_drops() {
+    let drops = Rc::new(Cell::new(0));
+    let mut cache = LRUCache::<DropCounter, 4>::default();
 
+    for _ in 0..4 {
+        assert!(cache.insert(DropCounter(drops.clone())).is_none());
+    }
+    assert_eq!(drops.get(), 0, "Nothing dropped while there is room.");
+
+    let evicted = cache.insert(DropCounter(drops.clone()));
+    assert_eq!(drops.get(), 0, "Evicted value is handed back, not dropped.");
+
+    drop(evicted);
+    assert_eq!(drops.get(), 1, "Evicted value dropped exactly once.");
+
+    cache.insert(DropCounter(drops.clone()));
+    assert_eq!(drops.get(), 2);
+
+    drop(cache);
+    assert_eq!(drops.get(), 6, "All remaining values dropped, none leaked.");
+}
+
+#[test]
+fn test_drain() {
     let mut cache = TestCache::default();
+    cache.extend(1..=5);
+    cache.touch(|x| *x == 3);
 
-    assert_eq!(cache.front(), None, "Nothing is in the front.");
+    assert_eq!(cache.drain().collect::<Vec<_>>(), [3, 5, 4, 2]);
+    assert!(cache.is_empty(), "Everything drained.");
 
-    cache.insert(first);
-    cache.insert(second);
+    cache.extend(1..=4);
+    let mut drain = cache.drain();
+    assert_eq!(drain.next(), Some(4));
+    assert_eq!(drain.next_back(), Some(1));
+    drop(drain);
+
+    assert_eq!(cache.len(), 0, "Rest removed when dropped early.");
+    assert_eq!(cache.items(), []);
+
+    cache.insert(6);
+    assert_eq!(cache.items(), [6], "Usable after draining.");
+}
+
+#[test]
+fn test_drain_drops() {
+    let drops = Rc::new(Cell::new(0));
+    let mut cache = LRUCache::<DropCounter, 4>::default();
+    for _ in 0..4 {
+        cache.insert(DropCounter(drops.clone()));
+    }
 
+    let first = cache.drain().next();
     assert_eq!(
-        cache.front(),
-        Some(&second),
-        "The last inserted item should be in the front."
+        drops.get(),
+        3,
+        "Undrained values dropped with the iterator."
     );
 
-    cache.touch(|x| *x == first);
+    drop(first);
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn test_index() {
+    let mut cache = TestCache::default();
+    cache.extend(0..=4);
+    cache.touch(|x| *x == 2);
+    cache.insert(5);
+
+    assert_eq!(cache.items(), [5, 2, 4, 3]);
+    assert_eq!(cache[0], 5, "Most recently used first.");
+    assert_eq!(cache[1], 2, "Logical order, not slot order.");
+    assert_eq!(cache[3], 3);
+}
+
+#[test]
+fn test_peek_nth() {
+    let mut cache = TestCache::default();
+    cache.extend(1..=4);
+    cache.touch(|x| *x == 2);
+
+    assert_eq!(cache.peek_nth(0), Some(&2), "Most recently used first.");
+    assert_eq!(cache.peek_nth(1), Some(&4));
+    assert_eq!(cache.peek_nth(3), Some(&1), "Last rank is the tail.");
+    assert_eq!(cache.peek_nth(4), None, "Out of range.");
+    assert_eq!(cache.items(), [2, 4, 3, 1], "Order not changed.");
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_index_out_of_range() {
+    let cache: TestCache = (1..=2).collect();
+
+    let _ = cache[2];
+}
+
+#[test]
+fn test_lookup() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+
+    let result = cache.lookup(|x| if *x == 5 { Some(()) } else { None });
+    assert_eq!(result, None, "Cache miss.");
+    assert_eq!(cache.items(), [4, 3, 2, 1], "Order not changed.");
+
+    // Cache hit
+    let result = cache.lookup(|x| if *x == 3 { Some(*x * 2) } else { None });
+    assert_eq!(result, Some(6), "Cache hit.");
+    assert_eq!(cache.items(), [3, 4, 2, 1], "Matching item moved to front.");
+}
+
+#[test]
+fn test_take() {
+    let mut cache = LRUCache::<String, 4>::default();
+    cache.extend(["a", "b", "c", "d"].map(String::from));
 
+    assert_eq!(cache.take(|x| x == "c").as_deref(), Some("c"));
     assert_eq!(
-        cache.front(),
-        Some(&first),
-        "Touched item should be in the front."
+        cache.items(),
+        ["d", "b", "a"],
+        "Survivors keep their order."
     );
+    assert_eq!(cache.take(|x| x == "z"), None, "Nothing taken on a miss.");
+
+    assert_eq!(cache.insert("e".to_string()), None, "Freed slot is reused.");
+    assert_eq!(cache.items(), ["e", "d", "b", "a"]);
+}
+
+#[test]
+fn test_remove_matching() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+
+    assert_eq!(cache.remove_matching(|x| *x == 5), None, "Nothing removed.");
+    assert_eq!(cache.remove_matching(|x| *x == 3), Some(3));
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.items(), [4, 2, 1], "Order of the rest preserved.");
+
+    assert_eq!(cache.insert(5), None, "Freed slot is reused.");
+    assert_eq!(cache.items(), [5, 4, 2, 1]);
+    assert_eq!(cache.insert(6), Some(1));
+
+    assert_eq!(cache.remove_matching(|x| *x == 6), Some(6), "Remove front.");
+    assert_eq!(cache.remove_matching(|x| *x == 2), Some(2), "Remove back.");
+    assert_eq!(cache.items(), [5, 4]);
+
+    assert_eq!(cache.remove_matching(|x| *x == 4), Some(4));
+    assert_eq!(cache.remove_matching(|x| *x == 5), Some(5));
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.items(), [], "Everything removed.");
+
+    cache.insert(7);
+    assert_eq!(cache.items(), [7]);
+}
+
+#[test]
+fn test_retain() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+    cache.insert(5);
+    cache.touch(|x| *x == 3);
+
+    cache.retain(|x| *x != 4);
+    assert_eq!(cache.items(), [3, 5, 2], "Survivors keep their order.");
+
+    cache.retain(|x| *x % 2 == 1);
+    assert_eq!(cache.items(), [3, 5]);
+    assert_eq!(cache.len(), 2);
+
+    cache.insert(6);
+    cache.insert(7);
+    assert_eq!(cache.items(), [7, 6, 3, 5], "Freed slots are reused.");
+
+    cache.retain(|_| false);
+    assert!(cache.is_empty());
+    assert_eq!(cache.items(), []);
+}
+
+#[quickcheck]
+fn test_retain_matches_filter(values: Vec<u8>, modulo: u8) -> bool {
+    let modulo = modulo.max(1);
+    let mut cache = LRUCache::<u8, 8>::default();
+    cache.extend(values);
+
+    let expected: Vec<u8> = cache
+        .items()
+        .into_iter()
+        .filter(|x| x % modulo == 0)
+        .collect();
+    cache.retain(|x| x % modulo == 0);
+
+    cache.items() == expected && cache.len() == expected.len()
+}
+
+#[quickcheck]
+fn test_extract_if_partitions(values: Vec<u8>, modulo: u8) -> bool {
+    let modulo = modulo.max(1);
+    let mut cache = LRUCache::<u8, 8>::default();
+    cache.extend(values);
+
+    let (removed, kept): (Vec<u8>, Vec<u8>) =
+        cache.items().into_iter().partition(|x| x % modulo == 0);
+    let extracted: Vec<u8> = cache.extract_if(|x| x % modulo == 0).collect();
+
+    extracted == removed && cache.items() == kept
+}
+
+#[test]
+fn test_pop_lru() {
+    let mut cache = TestCache::default();
+    assert_eq!(cache.pop_lru(), None, "Nothing to pop.");
+
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.touch(|x| *x == 1);
+
+    assert_eq!(cache.pop_lru(), Some(2));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.items(), [1, 3]);
+
+    cache.insert(4);
+    cache.insert(5);
+    assert_eq!(cache.len(), 4, "Popped slot is reused.");
+    assert_eq!(cache.items(), [5, 4, 1, 3]);
+
+    assert_eq!(cache.pop_lru(), Some(3));
+    assert_eq!(cache.pop_lru(), Some(1));
+    assert_eq!(cache.pop_lru(), Some(4));
+    assert_eq!(cache.pop_lru(), Some(5));
+    assert_eq!(cache.pop_lru(), None);
+    assert!(cache.is_empty());
+}
+
+// Walks every entry of a full cache through the mutable iterator used by
+// `touch` and `lookup`; meant to also be run under `cargo +nightly miri test`.
+#[test]
+fn test_scan_full_cache() {
+    let mut cache = LRUCache::<String, 4>::default();
+    cache.extend((0..6).map(|x| x.to_string()));
+
+    for x in ["2", "3", "4", "5"] {
+        assert!(cache.touch(|v| v == x), "Every entry is reachable.");
+    }
+    assert!(!cache.touch(|v| v == "6"), "Full scan on a miss.");
+
+    let found = cache.lookup(|v| if v == "2" { Some(v.len()) } else { None });
+    assert_eq!(found, Some(1));
+    assert_eq!(cache.items(), ["2", "5", "4", "3"]);
+}
+
+#[test]
+fn test_clear() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.clear();
+
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.items(), [], "All items evicted");
+
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+    assert_eq!(cache.items(), [4, 3, 2, 1]);
+    cache.clear();
+    assert_eq!(cache.items(), [], "All items evicted again");
+}
+
+#[test]
+fn test_clear_drops() {
+    let drops = Rc::new(Cell::new(0));
+    let mut cache = LRUCache::<DropCounter, 8>::default();
+    for _ in 0..3 {
+        cache.insert(DropCounter(drops.clone()));
+    }
+
+    cache.clear();
+    assert_eq!(
+        drops.get(),
+        3,
+        "Only the stored values dropped, not capacity."
+    );
+
+    cache.clear();
+    assert_eq!(drops.get(), 3, "Clearing an empty cache drops nothing.");
+
+    for _ in 0..5 {
+        cache.insert(DropCounter(drops.clone()));
+    }
+    drop(cache.pop_lru());
+    assert_eq!(drops.get(), 4);
+
+    cache.clear();
+    assert_eq!(drops.get(), 8, "Each value dropped exactly once.");
+
+    drop(cache);
+    assert_eq!(drops.get(), 8, "Nothing left to drop.");
+}
+
+#[quickcheck]
+fn test_touch(num: i16) {
+    let first: i32 = num.into();
+    let second = first + 1;
+    let third = first + 2;
+    let fourth = first + 3;
+
+    let mut cache = TestCache::default();
+
+    cache.insert(first);
+    cache.insert(second);
+    cache.insert(third);
+    cache.insert(fourth);
+
+    cache.touch(|x| *x == fourth + 1);
+
+    assert_eq!(
+        cache.items(),
+        [fourth, third, second, first],
+        "Nothing is touched."
+    );
+
+    cache.touch(|x| *x == second);
+
+    assert_eq!(
+        cache.items(),
+        [second, fourth, third, first],
+        "Touched item is moved to front."
+    );
+}
+
+#[test]
+fn test_touch_matching() {
+    let mut cache = LRUCache::<i32, 5>::default();
+    cache.extend(1..=5);
+
+    assert_eq!(cache.touch_matching(|x| *x > 5), 0);
+    assert_eq!(cache.items(), [5, 4, 3, 2, 1], "Nothing is touched.");
+
+    assert_eq!(cache.touch_matching(|x| *x % 2 == 1), 3);
+    assert_eq!(
+        cache.items(),
+        [5, 3, 1, 4, 2],
+        "Matches moved to front, keeping their relative order."
+    );
+
+    assert_eq!(cache.touch_matching(|x| *x % 2 == 0), 2);
+    assert_eq!(cache.items(), [4, 2, 5, 3, 1]);
+
+    assert_eq!(cache.touch_matching(|_| true), 5);
+    assert_eq!(cache.items(), [4, 2, 5, 3, 1], "Touching all keeps order.");
+}
+
+#[quickcheck]
+fn test_fetch(num: i16) {
+    let first: i32 = num.into();
+    let second = first + 1;
+    let third = first + 2;
+    let fourth = first + 3;
+
+    let mut cache = TestCache::default();
+
+    cache.insert(first);
+    cache.insert(second);
+    cache.insert(third);
+    cache.insert(fourth);
+
+    cache.fetch(|x| *x == fourth + 1);
+
+    assert_eq!(
+        cache.items(),
+        [fourth, third, second, first],
+        "Nothing is touched."
+    );
+
+    cache.fetch(|x| *x == second);
+
+    assert_eq!(
+        cache.items(),
+        [second, fourth, third, first],
+        "Fetched item is moved to front."
+    );
+}
+
+#[test]
+fn test_get_mut_no_touch() {
+    let mut cache = TestCache::default();
+    cache.extend(1..=4);
+
+    assert_eq!(cache.get_mut_no_touch(|x| *x == 5), None);
+
+    *cache.get_mut_no_touch(|x| *x == 2).unwrap() = 20;
+    assert_eq!(cache.items(), [4, 3, 20, 1], "Mutated in place, not moved.");
+}
+
+#[quickcheck]
+fn test_front(num: i16) {
+    let first: i32 = num.into();
+    let second = first + 1;
+
+    let mut cache = TestCache::default();
+
+    assert_eq!(cache.front(), None, "Nothing is in the front.");
+
+    cache.insert(first);
+    cache.insert(second);
+
+    assert_eq!(
+        cache.front(),
+        Some(&second),
+        "The last inserted item should be in the front."
+    );
+
+    cache.touch(|x| *x == first);
+
+    assert_eq!(
+        cache.front(),
+        Some(&first),
+        "Touched item should be in the front."
+    );
+}
+
+#[quickcheck]
+fn test_peek(num: i16) {
+    let first: i32 = num.into();
+    let second = first + 1;
+    let third = first + 2;
+
+    let mut cache = TestCache::default();
+
+    assert_eq!(cache.peek(|_| true), None, "Nothing to peek.");
+    assert_eq!(cache.peek_lru(), None, "Nothing to peek.");
+
+    cache.insert(first);
+    cache.insert(second);
+    cache.insert(third);
+
+    assert_eq!(cache.peek(|x| *x == second), Some(&second));
+    assert_eq!(cache.peek(|x| *x == third + 1), None);
+    assert_eq!(cache.peek_lru(), Some(&first));
+
+    assert_eq!(
+        cache.items(),
+        [third, second, first],
+        "Peeking does not reorder."
+    );
+}
+
+#[quickcheck]
+fn test_contains(num: i16) {
+    let first: i32 = num.into();
+    let second = first + 1;
+
+    let mut cache = TestCache::default();
+    assert!(!cache.contains(|_| true), "Empty cache contains nothing.");
+
+    cache.insert(first);
+    cache.insert(second);
+
+    assert!(cache.contains(|x| *x == first));
+    assert!(!cache.contains(|x| *x == second + 1));
+    assert_eq!(cache.items(), [second, first], "Order not changed.");
+}
+
+#[test]
+fn test_get_or_insert_with() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+
+    let mut calls = 0;
+    let hit = cache.get_or_insert_with(
+        |x| *x == 1,
+        || {
+            calls += 1;
+            10
+        },
+    );
+    assert_eq!(*hit, 1, "Cache hit.");
+    assert_eq!(calls, 0, "Nothing computed on a hit.");
+    assert_eq!(cache.items(), [1, 2], "Matching item moved to front.");
+
+    let miss = cache.get_or_insert_with(|x| *x == 3, || 3);
+    *miss += 1;
+    assert_eq!(cache.items(), [4, 1, 2], "Computed item inserted at front.");
+}
+
+#[test]
+fn test_access() {
+    let mut cache = LRUCache::<(i32, &str), 4>::default();
+    cache.extend([(1, "a"), (2, "b"), (3, "c")]);
+
+    let hit = cache.access(|x| x.0 == 1, (1, "new"));
+    assert_eq!(*hit, (1, "a"), "Original value kept.");
+    assert_eq!(
+        cache.items(),
+        [(1, "a"), (3, "c"), (2, "b")],
+        "Hit moved to front."
+    );
+
+    let miss = cache.access(|x| x.0 == 4, (4, "d"));
+    miss.1 = "e";
+    assert_eq!(
+        cache.items(),
+        [(4, "e"), (1, "a"), (3, "c"), (2, "b")],
+        "Miss inserted at front."
+    );
+}
+
+#[test]
+fn test_get_or_try_insert_with() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    let hit = cache.get_or_try_insert_with(|x| *x == 2, || Err("unused"));
+    assert_eq!(hit, Ok(&mut 2), "Cache hit.");
+    assert_eq!(cache.items(), [2, 4, 3, 1], "Matching item moved to front.");
+
+    let failed = cache.get_or_try_insert_with(|x| *x == 5, || Err("fetch failed"));
+    assert_eq!(failed, Err("fetch failed"), "Error propagated.");
+    assert_eq!(cache.items(), [2, 4, 3, 1], "Nothing inserted or evicted.");
+
+    let made = cache.get_or_try_insert_with(|x| *x == 5, || Ok::<_, ()>(5));
+    assert_eq!(made, Ok(&mut 5));
+    assert_eq!(
+        cache.items(),
+        [5, 2, 4, 3],
+        "Inserted at front, LRU evicted."
+    );
+}
+
+#[test]
+fn test_eq() {
+    let mut first = TestCache::default();
+    first.insert(1);
+    first.insert(2);
+    first.insert(3);
+
+    let mut second = TestCache::default();
+    second.insert(9);
+    second.insert(8);
+    second.insert(3);
+    second.insert(7);
+    second.insert(1);
+    second.insert(2);
+    second.insert(3);
+    second.remove_matching(|x| *x == 3);
+    second.insert(3);
+    second.pop_lru();
+
+    assert_eq!(second.items(), [3, 2, 1]);
+    assert_eq!(first, second, "Same values in the same order.");
+
+    second.touch(|x| *x == 1);
+    assert_ne!(first, second, "Same values in a different order.");
+
+    second.pop_lru();
+    assert_ne!(first, second, "Different lengths.");
+}
+
+#[quickcheck]
+fn test_back(num: i16) {
+    let first: i32 = num.into();
+    let second = first + 1;
+
+    let mut cache = TestCache::default();
+    assert_eq!(cache.back(), None, "Nothing is in the back.");
+    assert_eq!(cache.back_mut(), None, "Nothing is in the back.");
+
+    cache.insert(first);
+    cache.insert(second);
+    assert_eq!(
+        cache.back(),
+        Some(&first),
+        "The first inserted item should be in the back."
+    );
+
+    cache.touch(|x| *x == first);
+    assert_eq!(cache.back(), Some(&second), "Touched item leaves the back.");
+
+    *cache.back_mut().unwrap() += 2;
+    assert_eq!(cache.items(), [first, second + 2]);
+
+    cache.pop_lru();
+    cache.pop_lru();
+    assert_eq!(cache.back(), None, "Nothing is in the back.");
+}
+
+#[test]
+fn test_front_after_removing_last() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    assert_eq!(cache.remove_matching(|x| *x == 1), Some(1));
+
+    assert_eq!(cache.front(), None, "Nothing is in the front.");
+    assert_eq!(cache.front_mut(), None, "Nothing is in the front.");
+
+    cache.insert(2);
+    cache.insert(3);
+    cache.pop_lru();
+    cache.pop_lru();
+
+    assert_eq!(cache.front(), None, "Nothing is in the front.");
+    assert_eq!(cache.fetch(|_| true), None);
+}
+
+#[test]
+fn test_entry() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+
+    *cache
+        .entry(|x| *x == 1)
+        .and_modify(|x| *x += 10)
+        .or_insert(0) += 1;
+    assert_eq!(cache.items(), [12, 2], "Match modified and moved to front.");
+
+    *cache
+        .entry(|x| *x == 5)
+        .and_modify(|x| *x += 10)
+        .or_insert(5) += 1;
+    assert_eq!(cache.items(), [6, 12, 2], "Missing value inserted.");
+}
+
+#[test]
+fn test_entry_when_full() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+
+    let val = cache.entry(|x| *x == 5).or_insert_with(|| 5);
+    *val *= 2;
+    assert_eq!(cache.items(), [10, 4, 3, 2], "LRU value evicted.");
+}
+
+#[test]
+fn test_insert_many() {
+    let mut cache = TestCache::default();
+
+    assert_eq!(cache.insert_many(1..=6), [1, 2], "Evictions in order.");
+    assert_eq!(cache.items(), [6, 5, 4, 3]);
+
+    assert!(cache.insert_many([]).is_empty(), "Nothing evicted.");
+}
+
+#[test]
+fn test_insert_reuses_removed_slots() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    assert_eq!(cache.remove_matching(|x| *x == 3), Some(3));
+    cache.retain(|x| *x != 2);
+    assert_eq!(cache.len(), 2);
+
+    assert_eq!(cache.insert(5), None, "Freed slot reused without eviction.");
+    assert_eq!(cache.insert(6), None, "Freed slot reused without eviction.");
+    assert_eq!(cache.items(), [6, 5, 4, 1]);
+
+    assert_eq!(
+        cache.insert(7),
+        Some(1),
+        "Full again, so the LRU is evicted."
+    );
+}
+
+#[quickcheck]
+fn removals_never_strand_slots(ops: Vec<(bool, u8)>) {
+    let mut cache = LRUCache::<u8, 4>::default();
+
+    for (remove, val) in ops {
+        if remove {
+            cache.remove_matching(|x| *x % 4 == val % 4);
+        } else {
+            let was_full = cache.is_full();
+            assert_eq!(cache.insert(val).is_some(), was_full);
+        }
+    }
+}
+
+#[test]
+fn test_zero_capacity() {
+    let mut cache = LRUCache::<i32, 0>::default();
+
+    assert_eq!(cache.insert(1), Some(1), "Value evicted immediately.");
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.front(), None);
+    assert_eq!(cache.back(), None);
+    assert!(!cache.touch(|x| *x == 1));
+    assert_eq!(cache.lookup(|x| Some(*x)), None);
+    assert_eq!(cache.pop_lru(), None);
+    assert_eq!(cache.try_insert(2), Err(2));
+    assert_eq!(cache.iter().count(), 0);
+}
+
+#[test]
+fn test_debug() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4, 5]);
+    cache.touch(|x| *x == 3);
+
+    assert_eq!(format!("{:?}", cache), "LRUCache [3, 5, 4, 2]");
+    assert_eq!(
+        format!("{:?}", TestCache::default()),
+        "LRUCache []",
+        "Empty cache."
+    );
+
+    let alternate = format!("{:#?}", cache);
+    assert!(
+        alternate.contains("head: Some("),
+        "Head shown: {}",
+        alternate
+    );
+    assert!(
+        alternate.contains("tail: Some("),
+        "Tail shown: {}",
+        alternate
+    );
+}
+
+#[test]
+fn test_boxed() {
+    // About 24 MB, far more than a test thread's stack
+    let mut cache = LRUCache::<u64, 1_000_000>::boxed();
+
+    assert!(cache.is_empty());
+    assert_eq!(cache.capacity(), 1_000_000);
+
+    cache.extend(0..1_000_001);
+    assert_eq!(cache.len(), 1_000_000);
+    assert_eq!(cache.front(), Some(&1_000_000));
+    assert_eq!(cache.back(), Some(&1), "Oldest value evicted.");
+}
+
+// Small enough to also run under `cargo +nightly miri test`, which checks the
+// zeroed columns are read as empty ones.
+#[test]
+fn test_boxed_owned_values() {
+    let mut cache = LRUCache::<String, 3>::boxed();
+    assert!(cache.is_empty());
+    assert_eq!(cache.iter().next(), None);
+
+    cache.extend(["a", "b", "c", "d"].map(String::from));
+    assert_eq!(cache.items(), ["d", "c", "b"]);
+}
+
+#[test]
+fn test_fetch_ref() {
+    let mut cache = LRUCache::<String, 4>::default();
+    cache.extend(["a", "b", "c"].map(String::from));
+
+    assert_eq!(cache.fetch_ref(|x| x == "a").map(String::as_str), Some("a"));
+    assert_eq!(cache.front().map(String::as_str), Some("a"), "Hit fronted.");
+    assert_eq!(cache.fetch_ref(|x| x == "z"), None, "Cache miss.");
+}
+
+#[test]
+fn test_move_to_front_index() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    assert!(cache.move_to_front_index(2));
+    assert_eq!(cache.items(), [2, 4, 3, 1]);
+
+    assert!(cache.move_to_front_index(0), "Front stays at the front.");
+    assert_eq!(cache.items(), [2, 4, 3, 1]);
+
+    assert!(!cache.move_to_front_index(4), "Out of range.");
+    assert_eq!(cache.items(), [2, 4, 3, 1], "Order not changed.");
+}
+
+#[test]
+fn test_demote_front() {
+    let mut cache = TestCache::default();
+    assert!(!cache.demote_front(), "Nothing to demote.");
+
+    cache.extend([1, 2, 3, 4]);
+    assert!(cache.demote_front());
+    assert_eq!(cache.items(), [3, 2, 1, 4], "Front moved to the back.");
+    assert_eq!(cache.insert(5), Some(4), "Demoted value evicted next.");
+
+    let mut single = TestCache::default();
+    single.insert(1);
+    assert!(single.demote_front());
+    assert_eq!(single.items(), [1]);
+}
+
+#[test]
+fn test_extend_trait() {
+    fn fill<E: Extend<i32>>(target: &mut E) {
+        target.extend(1..=6);
+    }
+
+    let mut cache = TestCache::default();
+    fill(&mut cache);
+
+    assert_eq!(
+        cache.items(),
+        [6, 5, 4, 3],
+        "Only the last C values remain."
+    );
+}
+
+#[test]
+fn test_resize_into() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    let grown = cache.clone().resize_into::<8>();
+    assert_eq!(grown.capacity(), 8);
+    assert_eq!(grown.len(), 4);
+    assert_eq!(grown.items(), [4, 3, 2, 1], "Order preserved.");
+
+    let shrunk = cache.resize_into::<2>();
+    assert_eq!(
+        shrunk.items(),
+        [4, 3],
+        "Least recently used values dropped."
+    );
+}
+
+#[test]
+fn test_touch_only_element() {
+    let mut single = LRUCache::<i32, 1>::default();
+    single.insert(1);
+
+    assert!(single.touch(|x| *x == 1));
+    assert_eq!(single.front(), Some(&1));
+    assert_eq!(
+        single.back(),
+        Some(&1),
+        "Front and back are the same value."
+    );
+    assert_eq!(single.insert(2), Some(1));
+    assert_eq!(single.items(), [2]);
+
+    // Down to one element after removals, in a larger cache
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3]);
+    cache.retain(|x| *x == 2);
+
+    assert!(cache.touch(|x| *x == 2));
+    assert_eq!(cache.front(), Some(&2));
+    assert_eq!(cache.back(), Some(&2));
+
+    cache.insert(4);
+    assert_eq!(cache.items(), [4, 2]);
+    assert_eq!(
+        cache.back(),
+        Some(&2),
+        "Tail still points at the old value."
+    );
+}
+
+#[test]
+fn test_as_mru_lru_vec() {
+    // Not `Clone`, so `items` is unavailable
+    #[derive(Debug, PartialEq)]
+    struct Value(i32);
+
+    let mut cache = LRUCache::<Value, 4>::default();
+    cache.extend([1, 2, 3].map(Value));
+    cache.touch(|x| x.0 == 1);
+
+    assert_eq!(cache.as_mru_vec(), [&Value(1), &Value(3), &Value(2)]);
+    assert_eq!(cache.as_lru_vec(), [&Value(2), &Value(3), &Value(1)]);
+}
+
+#[test]
+fn test_hash() {
+    use std::collections::HashSet;
+
+    let mut first = TestCache::default();
+    first.extend([1, 2, 3]);
+
+    // Same order, different slots
+    let mut second = TestCache::default();
+    second.extend([3, 1, 2]);
+    second.touch(|x| *x == 3);
+    assert_eq!(first, second);
+
+    let mut reordered = first.clone();
+    reordered.touch(|x| *x == 1);
+
+    let set: HashSet<_> = [first, second, reordered].into_iter().collect();
+    assert_eq!(set.len(), 2, "Equal caches hash equal.");
+}
+
+#[test]
+fn test_back_mut_no_touch() {
+    let mut cache = TestCache::default();
+    assert_eq!(cache.back_mut_no_touch(), None);
+
+    cache.extend([1, 2, 3]);
+    *cache.back_mut_no_touch().unwrap() += 10;
+
+    assert_eq!(cache.items(), [3, 2, 11], "Order not changed.");
+    assert_eq!(cache.insert(4), None);
+    assert_eq!(cache.insert(5), Some(11), "Still the next to be evicted.");
+}
+
+#[test]
+fn test_ranked() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4, 5]);
+    cache.touch(|x| *x == 2);
+
+    let ranked: Vec<_> = cache.ranked().collect();
+    assert_eq!(ranked, [(0, &2), (1, &5), (2, &4), (3, &3)]);
+}
+
+#[test]
+fn test_split_off_lru() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    assert_eq!(cache.split_off_lru(2), [1, 2], "Cold tail in LRU order.");
+    assert_eq!(cache.items(), [4, 3]);
+
+    assert!(cache.split_off_lru(2).is_empty(), "Nothing to split off.");
+    assert!(cache.split_off_lru(10).is_empty(), "Nothing to split off.");
+    assert_eq!(cache.items(), [4, 3]);
+
+    assert_eq!(cache.split_off_lru(0), [3, 4]);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_drain_n() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    assert_eq!(cache.drain_n(2), [4, 3], "Hottest values, MRU first.");
+    assert_eq!(cache.items(), [2, 1], "Rest left in order.");
+
+    assert!(cache.drain_n(0).is_empty());
+    assert_eq!(cache.drain_n(10), [2, 1], "Everything drained.");
+    assert!(cache.is_empty());
+
+    cache.extend([5, 6, 7, 8]);
+    assert_eq!(cache.len(), 4, "Freed slots reused.");
+}
+
+#[test]
+fn test_front_ranked() {
+    let mut cache = TestCache::default();
+    assert_eq!(cache.front_ranked(), None);
+    assert_eq!(cache.front_with_rest(), None);
+
+    cache.insert(1);
+    assert_eq!(cache.front_with_rest(), Some((&1, 0)), "Nothing behind it.");
+
+    cache.extend([2, 3]);
+    assert_eq!(cache.front_ranked(), Some((0, &3)));
+    assert_eq!(cache.front_with_rest(), Some((&3, 2)));
+}
+
+#[test]
+fn test_merge() {
+    let mut first = LRUCache::<&str, 4>::default();
+    first.extend(["a1", "a2"]);
+
+    let mut second = LRUCache::<&str, 4>::default();
+    second.extend(["b1", "b2"]);
+
+    first.merge(second.clone());
+    assert_eq!(first.items(), ["b2", "b1", "a2", "a1"]);
+
+    // Duplicates are not repeated, and the coldest values make way
+    let mut third = LRUCache::<&str, 4>::default();
+    third.extend(["c1", "b1"]);
+
+    first.merge(third);
+    assert_eq!(first.items(), ["b1", "c1", "b2", "a2"]);
+
+    // Rejected values are left out
+    let mut picky = LRUCache::<i32, 2>::with_admission_policy(|candidate: &i32, victim: &i32| {
+        candidate >= victim
+    });
+    picky.extend([5, 3]);
+
+    let mut offered = LRUCache::<i32, 2>::new();
+    offered.extend([7, 1]);
+
+    picky.merge(offered);
+    assert_eq!(picky.items(), [7, 3]);
+}
+
+#[test]
+fn test_lookup_with_touch() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3]);
+
+    let find = |val| move |x: &i32| if *x == val { Some(x * 10) } else { None };
+
+    assert_eq!(
+        cache.lookup_with_touch(find(3)),
+        (Some(30), false),
+        "At front."
+    );
+    assert_eq!(cache.lookup_with_touch(find(1)), (Some(10), true), "Moved.");
+    assert_eq!(cache.items(), [1, 3, 2]);
+    assert_eq!(
+        cache.lookup_with_touch(find(9)),
+        (None, false),
+        "Cache miss."
+    );
+}
+
+#[test]
+fn test_boxed_values() {
+    use lru_rs::BoxedLRUCache;
+    use std::mem::size_of;
+
+    let mut cache = BoxedLRUCache::<[u8; 1024], 4>::default();
+    assert!(
+        size_of::<BoxedLRUCache<[u8; 1024], 4>>() < 1024,
+        "Only pointers stored inline."
+    );
+
+    for i in 0..5 {
+        cache.insert(Box::new([i; 1024]));
+    }
+
+    let evicted = cache.insert(Box::new([9; 1024])).unwrap();
+    assert_eq!(evicted[0], 1, "LRU value evicted.");
+    assert!(cache.touch(|x| x[0] == 4));
+
+    let mut functions = BoxedLRUCache::<dyn Fn(i32) -> i32, 2>::default();
+    functions.insert(Box::new(|x| x + 1));
+    functions.insert(Box::new(|x| x * 2));
+    assert_eq!(
+        functions.iter().map(|f| f(10)).collect::<Vec<_>>(),
+        [20, 11]
+    );
+}
+
+#[test]
+fn test_position() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    assert_eq!(cache.position(|x| *x == 1), Some(3), "Tail is rank 3.");
+    assert_eq!(cache.position(|x| *x == 4), Some(0));
+    assert_eq!(cache.position(|x| *x == 9), None);
+    assert_eq!(cache.items(), [4, 3, 2, 1], "Order not changed.");
+
+    assert_eq!(cache.lookup_position(|x| *x == 2), Some(2), "Rank before.");
+    assert_eq!(cache.position(|x| *x == 2), Some(0), "Then moved to front.");
+    assert_eq!(cache.lookup_position(|x| *x == 9), None);
+}
+
+#[test]
+fn test_get_disjoint_mut() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3]);
+
+    let [first, missing, third] =
+        cache.get_disjoint_mut([|x: &i32| *x == 1, |x: &i32| *x == 9, |x: &i32| *x == 3]);
+    assert!(missing.is_none());
+
+    let (first, third) = (first.unwrap(), third.unwrap());
+    std::mem::swap(first, third);
+    *first += 10;
+
+    assert_eq!(cache.items(), [1, 2, 13], "Order not changed.");
+}
+
+#[test]
+#[should_panic(expected = "predicates matched the same value")]
+fn test_get_disjoint_mut_overlap() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3]);
+
+    cache.get_disjoint_mut([|x: &i32| *x > 1, |x: &i32| *x == 3]);
+}
+
+#[test]
+fn test_index_mut() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    cache[1] = 99;
+    cache[0] += 10;
+    assert_eq!(cache.items(), [14, 99, 2, 1], "Order not changed.");
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the len is 2 but the index is 2")]
+fn test_index_mut_out_of_range() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2]);
+
+    cache[2] = 0;
+}
+
+#[test]
+fn test_from_array() {
+    let mut cache = TestCache::from_array([1, 2, 3, 4]);
+
+    assert!(cache.is_full());
+    assert_eq!(cache.items(), [4, 3, 2, 1]);
+    assert_eq!(cache.insert(5), Some(1), "First element is the LRU.");
+}
+
+#[test]
+fn test_extract_if() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    let removed: Vec<_> = cache.extract_if(|x| x % 2 == 1).collect();
+    assert_eq!(removed, [3, 1], "Most recent first.");
+    assert_eq!(cache.items(), [4, 2], "Survivors keep their order.");
+    assert_eq!(cache.extract_if(|_| false).next(), None);
+
+    cache.extend([5, 6]);
+    assert_eq!(cache.extract_if(|_| true).next(), Some(6), "Stops early.");
+    assert_eq!(cache.items(), [5, 4, 2], "Unvisited values kept.");
+
+    cache.insert(7);
+    assert_eq!(cache.items(), [7, 5, 4, 2], "Freed slots reused.");
+}
+
+#[test]
+fn test_removal_moves_values() {
+    // Not `Clone`, and the address of the box shows whether it was moved
+    struct MoveOnly(Box<i32>);
+
+    let values: Vec<_> = (0..6).map(|i| MoveOnly(Box::new(i))).collect();
+    let addresses: Vec<*const i32> = values.iter().map(|v| &*v.0 as *const _).collect();
+
+    let mut cache = LRUCache::<MoveOnly, 4>::default();
+    let mut values = values.into_iter();
+
+    for val in values.by_ref().take(4) {
+        assert!(cache.insert(val).is_none());
+    }
+
+    let evicted = cache.insert(values.next().unwrap()).unwrap();
+    assert_eq!(
+        &*evicted.0 as *const _, addresses[0],
+        "Evicted value moved out."
+    );
+
+    let popped = cache.pop_lru().unwrap();
+    assert_eq!(
+        &*popped.0 as *const _, addresses[1],
+        "Popped value moved out."
+    );
+
+    let removed = cache.remove_matching(|v| *v.0 == 3).unwrap();
+    assert_eq!(
+        &*removed.0 as *const _, addresses[3],
+        "Removed value moved out."
+    );
+
+    cache.insert(values.next().unwrap());
+    let extracted = cache.extract_if(|v| *v.0 == 2).next().unwrap();
+    assert_eq!(
+        &*extracted.0 as *const _, addresses[2],
+        "Extracted value moved out."
+    );
+
+    let remaining: Vec<_> = cache.into_iter().map(|v| &*v.0 as *const _).collect();
+    assert_eq!(remaining, [addresses[5], addresses[4]]);
+}
+
+#[test]
+fn test_front_entry() {
+    use lru_rs::FrontGuard;
+
+    let mut cache = TestCache::default();
+    assert!(cache.front_entry().is_none());
+
+    cache.extend([1, 2, 3]);
+
+    *cache.front_entry().unwrap() += 10;
+    assert_eq!(cache.items(), [13, 2, 1], "Kept at the front.");
+
+    let mut front = cache.front_entry().unwrap();
+    *front += 10;
+    FrontGuard::demote(&mut front);
+    assert_eq!(*front, 23, "Still accessible until dropped.");
+    drop(front);
+
+    assert_eq!(cache.items(), [2, 1, 23], "Demoted to the back.");
+    assert_eq!(cache.insert(4), None);
+    assert_eq!(cache.insert(5), Some(23), "Demoted value evicted first.");
+}
+
+#[test]
+fn test_insert_or_front() {
+    let mut cache = TestCache::default();
+
+    for i in 1..=4 {
+        assert_eq!(cache.insert_or_front(i), Ok(()));
+    }
+
+    let front = cache.insert_or_front(5).unwrap_err();
+    assert_eq!(*front, 4, "Most recently used value returned.");
+    *front += 5;
+
+    assert_eq!(cache.items(), [9, 3, 2, 1], "Nothing evicted.");
+}
+
+#[test]
+fn test_insert_get() {
+    let mut cache = TestCache::default();
+
+    let (front, evicted) = cache.insert_get(1);
+    assert_eq!((*front, evicted), (1, None), "Spare capacity.");
+
+    cache.extend([2, 3, 4]);
+    let (front, evicted) = cache.insert_get(5);
+    assert_eq!(*front, 5, "New value returned.");
+    assert_eq!(evicted, Some(1), "LRU value evicted.");
+
+    *front += 5;
+    assert_eq!(cache.items(), [10, 4, 3, 2]);
+}
+
+#[test]
+fn test_zero_capacity_eviction_hook() {
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let seen = evicted.clone();
+    let mut cache = LRUCache::<i32, 0>::with_eviction_hook(move |x| seen.lock().unwrap().push(*x));
+
+    assert_eq!(cache.insert(1), Some(1), "Value handed straight back.");
+    assert_eq!(*evicted.lock().unwrap(), [], "Not reported as an eviction.");
+}
+
+#[test]
+fn test_touch_front() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3]);
+
+    let mut calls = 0;
+    assert!(cache.touch(|x| {
+        calls += 1;
+        *x == 3
+    }));
+    assert_eq!(calls, 1, "Only the front value checked.");
+    assert_eq!(cache.items(), [3, 2, 1], "Order not changed.");
+
+    assert_eq!(cache.fetch(|x| *x == 3), Some(&mut 3));
+    assert_eq!(
+        cache.fetch(|x| *x == 1),
+        Some(&mut 1),
+        "Other values found."
+    );
+    assert_eq!(cache.items(), [1, 3, 2]);
+}
+
+#[test]
+fn test_touch_checks_each_value_once() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3]);
+
+    let mut calls = 0;
+    assert!(!cache.touch(|_| {
+        calls += 1;
+        false
+    }));
+    assert_eq!(calls, 3, "Every value checked exactly once on a miss.");
+}
+
+#[test]
+fn test_count_matching() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    assert_eq!(cache.count_matching(|x| *x > 2), 2);
+    assert_eq!(cache.count_matching(|_| false), 0);
+    assert_eq!(cache.items(), [4, 3, 2, 1], "Order not changed.");
+}
+
+#[test]
+fn test_walk() {
+    let mut cache = TestCache::default();
+    cache.extend([1, 2, 3, 4]);
+
+    let mut seen = Vec::new();
+    cache.walk(|x| {
+        seen.push(*x);
+        if *x == 3 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(seen, [4, 3], "Stopped at the break.");
+
+    seen.clear();
+    cache.walk(|x| {
+        seen.push(*x);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(seen, [4, 3, 2, 1], "Visits everything without a break.");
+    assert_eq!(cache.items(), [4, 3, 2, 1], "Order not changed.");
+}
+
+#[test]
+fn test_to_sorted_vec_by() {
+    let mut cache = TestCache::default();
+    cache.extend([3, 1, 4, 2]);
+
+    assert_eq!(cache.to_sorted_vec_by(|a, b| a.cmp(b)), [1, 2, 3, 4]);
+    assert_eq!(cache.to_sorted_vec_by(|a, b| b.cmp(a)), [4, 3, 2, 1]);
+
+    // A stable sort keeps the recency order of ties
+    assert_eq!(
+        cache.to_sorted_vec_by(|a, b| (a % 2).cmp(&(b % 2))),
+        [2, 4, 1, 3]
+    );
+    assert_eq!(cache.items(), [2, 4, 1, 3], "Cache not changed.");
+}
+
+#[test]
+fn test_remaining() {
+    let mut cache = TestCache::default();
+    assert_eq!(cache.remaining(), 4);
+
+    cache.insert(1);
+    assert_eq!(cache.remaining(), 3);
+
+    cache.extend([2, 3, 4, 5]);
+    assert_eq!(cache.remaining(), 0);
+    assert!(cache.is_full());
+}
+
+#[quickcheck]
+fn tail_is_least_recently_used(ops: Vec<(u8, u8)>) {
+    let mut cache = TestCache::default();
+    // Most recently used first
+    let mut model: Vec<i32> = Vec::new();
+
+    for (op, val) in ops {
+        let val = i32::from(val % 8);
+
+        match op % 3 {
+            0 => {
+                cache.insert(val);
+                model.insert(0, val);
+                model.truncate(4);
+            }
+            1 => {
+                cache.touch(|x| *x == val);
+                if let Some(i) = model.iter().position(|x| *x == val) {
+                    let touched = model.remove(i);
+                    model.insert(0, touched);
+                }
+            }
+            _ => {
+                cache.clear();
+                model.clear();
+            }
+        }
+
+        assert_eq!(cache.back(), model.last(), "Tail is the LRU value.");
+        assert_eq!(cache.front(), model.first(), "Head is the MRU value.");
+    }
+}
+
+// Checks the links in both directions against a plain model after every
+// operation, so a stale `prev`, `next`, `head` or `tail` shows up as soon as
+// it is introduced.
+#[quickcheck]
+fn test_list_invariants(ops: Vec<(u8, u8)>) {
+    let mut cache = LRUCache::<u8, 5>::default();
+    // Most recently used first
+    let mut model: Vec<u8> = Vec::new();
+
+    let refresh = |model: &mut Vec<u8>, val: u8| match model.iter().position(|x| *x == val) {
+        Some(i) => {
+            let touched = model.remove(i);
+            model.insert(0, touched);
+            true
+        }
+        None => false,
+    };
+
+    for (op, val) in ops {
+        let val = val % 8;
+
+        match op % 5 {
+            0 | 1 => {
+                cache.insert(val);
+                model.insert(0, val);
+                model.truncate(5);
+            }
+            2 => {
+                assert_eq!(cache.touch(|x| *x == val), refresh(&mut model, val));
+            }
+            3 => {
+                let found = cache.lookup(|x| (*x == val).then_some(*x));
+                assert_eq!(found.is_some(), refresh(&mut model, val));
+            }
+            _ => match op % 4 {
+                0 => {
+                    cache.clear();
+                    model.clear();
+                }
+                _ => {
+                    let removed = cache.remove_matching(|x| *x == val);
+                    let expected = model
+                        .iter()
+                        .position(|x| *x == val)
+                        .map(|i| model.remove(i));
+                    assert_eq!(removed, expected);
+                }
+            },
+        }
+
+        assert_eq!(cache.len(), model.len(), "Length matches the walk.");
+        assert!(
+            cache.iter().eq(model.iter()),
+            "Following next from head visits every value in order."
+        );
+        assert!(
+            cache.iter().rev().eq(model.iter().rev()),
+            "Following prev from tail visits every value in reverse."
+        );
+        assert_eq!(cache.front(), model.first());
+        assert_eq!(cache.back(), model.last());
+    }
 }
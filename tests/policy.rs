@@ -0,0 +1,69 @@
+#![cfg(feature = "alloc")]
+
+use lru_rs::{AccessPolicy, LRUCache, Lru, StepUp};
+
+// Runs the same accesses against a cache using the policy `P`
+fn accessed<P: AccessPolicy>() -> LRUCache<i32, 4, P> {
+    let mut cache = LRUCache::<i32, 4, P>::new();
+    cache.extend([1, 2, 3, 4]);
+
+    cache.touch(|x| *x == 1);
+    cache.touch(|x| *x == 1);
+    cache.lookup(|x| if *x == 3 { Some(()) } else { None });
+
+    cache
+}
+
+#[test]
+fn test_lru_policy() {
+    let cache = accessed::<Lru>();
+
+    assert_eq!(
+        cache.items(),
+        [3, 1, 4, 2],
+        "Accessed values moved to front."
+    );
+}
+
+#[test]
+fn test_step_up_policy() {
+    let mut cache = accessed::<StepUp>();
+
+    assert_eq!(cache.items(), [4, 3, 1, 2], "Accessed values moved up one.");
+
+    *cache.fetch(|x| *x == 4).unwrap() += 10;
+    assert_eq!(cache.items(), [14, 3, 1, 2], "Front value stays at front.");
+
+    *cache.fetch(|x| *x == 2).unwrap() += 10;
+    assert_eq!(
+        cache.items(),
+        [14, 3, 12, 1],
+        "Fetch returns the moved value."
+    );
+
+    assert_eq!(cache.insert(5), Some(1), "Back value still evicted.");
+}
+
+#[test]
+fn test_step_up_entry() {
+    let mut cache = LRUCache::<i32, 4, StepUp>::new();
+    cache.extend([1, 2, 3]);
+
+    *cache.entry(|x| *x == 1).or_insert(0) += 10;
+    assert_eq!(cache.items(), [3, 11, 2]);
+
+    *cache.get_or_insert_with(|x| *x == 7, || 7) += 1;
+    assert_eq!(cache.items(), [8, 3, 11, 2], "Inserted values go in front.");
+}
+
+#[test]
+fn test_explicit_reordering_ignores_policy() {
+    let mut cache = LRUCache::<i32, 4, StepUp>::new();
+    cache.extend([1, 2, 3, 4]);
+
+    cache.move_to_front_index(3);
+    assert_eq!(cache.items(), [1, 4, 3, 2]);
+
+    cache.touch_matching(|x| *x == 2);
+    assert_eq!(cache.items(), [2, 1, 4, 3]);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "std")]
+
+use std::time::Duration;
+
+use lru_rs::prelude::*;
+
+#[test]
+fn test_prelude() {
+    let mut cache = LRUCache::<i32, 4, StepUp>::new();
+    cache.extend([1, 2, 3]);
+
+    let iter: Iter<'_, i32> = cache.iter();
+    assert_eq!(iter.len(), 3);
+
+    let generic: &mut dyn Cache<i32> = &mut cache;
+    assert!(generic.touch(&mut |x| *x == 2));
+
+    let extracted: ExtractIf<'_, i32, 4, _> = cache.extract_if(|x| *x == 1);
+    assert_eq!(extracted.collect::<Vec<_>>(), [1]);
+
+    let drained: Drain<'_, i32, 4> = cache.drain();
+    assert_eq!(drained.collect::<Vec<_>>(), [2, 3]);
+
+    cache.extend([1, 2]);
+    let owned: IntoIter<i32, 4, StepUp> = cache.into_iter();
+    assert_eq!(owned.len(), 2);
+
+    let mut keyed = KeyedLRUCache::<&str, i32, 2>::default();
+    keyed.put("a", 1);
+    assert_eq!(keyed.get(&"a"), Some(&1));
+
+    let mut dynamic = DynLRUCache::with_capacity(2);
+    dynamic.insert(1);
+    assert_eq!(dynamic.len(), 1);
+
+    let clock = SystemClock;
+    let mut ttl = TtlLRUCache::<i32, 2>::with_clock(clock);
+    ttl.insert(1, Duration::from_secs(60));
+    assert!(clock.now() <= clock.now());
+    assert_eq!(ttl.get(|x| *x == 1), Some(&1));
+}
@@ -0,0 +1,67 @@
+use lru_rs::SampledLRUCache;
+
+type TestCache = SampledLRUCache<i32, 4>;
+
+#[test]
+fn test_insert_bounds_len() {
+    let mut cache = TestCache::default();
+
+    for i in 0..4 {
+        assert_eq!(cache.insert(i), None, "Spare capacity, nothing evicted.");
+    }
+
+    for i in 4..100 {
+        assert!(cache.insert(i).is_some(), "Full, so a value is evicted.");
+        assert_eq!(cache.len(), 4);
+    }
+
+    assert!(cache.iter().any(|x| *x == 99), "Newest value kept.");
+}
+
+#[test]
+fn test_evicts_older_of_sample() {
+    for seed in 1..=64 {
+        let mut cache = SampledLRUCache::<i32, 2>::with_seed(seed);
+        cache.insert(1);
+        cache.insert(2);
+        cache.touch(|x| *x == 1);
+
+        // With two slots both are always sampled, and 2 is the older
+        assert_eq!(cache.insert(3), Some(2), "Seed {}.", seed);
+    }
+}
+
+#[test]
+fn test_single_slot() {
+    let mut cache = SampledLRUCache::<i32, 1>::with_seed(3);
+    cache.insert(1);
+
+    assert_eq!(cache.insert(2), Some(1), "Only slot evicted.");
+    assert_eq!(cache.iter().copied().collect::<Vec<_>>(), [2]);
+}
+
+#[test]
+fn test_lookup() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+
+    assert_eq!(
+        cache.lookup(|x| if *x == 2 { Some(x * 10) } else { None }),
+        Some(20)
+    );
+    assert_eq!(
+        cache.lookup(|x| if *x == 3 { Some(*x) } else { None }),
+        None
+    );
+    assert!(cache.touch(|x| *x == 1));
+    assert!(!cache.touch(|x| *x == 3));
+}
+
+#[test]
+fn test_zero_capacity() {
+    let mut cache = SampledLRUCache::<i32, 0>::default();
+
+    assert_eq!(cache.insert(1), Some(1), "Value evicted immediately.");
+    assert!(cache.is_empty());
+}
@@ -0,0 +1,29 @@
+#![cfg(all(feature = "serde", feature = "alloc"))]
+
+use lru_rs::LRUCache;
+
+type TestCache = LRUCache<i32, 4>;
+
+#[test]
+fn test_round_trip() {
+    let mut cache = TestCache::default();
+    cache.insert(1);
+    cache.insert(2);
+    cache.insert(3);
+    cache.insert(4);
+    cache.insert(5);
+    cache.touch(|x| *x == 3);
+
+    let json = serde_json::to_string(&cache).unwrap();
+    assert_eq!(json, "[3,5,4,2]", "Serialized from most to least recent.");
+
+    let restored: TestCache = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.items(), cache.items(), "Recency order preserved.");
+    assert_eq!(restored.len(), 4);
+}
+
+#[test]
+fn test_reject_overflow() {
+    let result = serde_json::from_str::<TestCache>("[1,2,3,4,5]");
+    assert!(result.is_err(), "More values than capacity.");
+}
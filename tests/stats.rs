@@ -0,0 +1,48 @@
+#![cfg(feature = "stats")]
+
+use lru_rs::{CacheStats, LRUCache};
+
+type TestCache = LRUCache<i32, 2>;
+
+#[test]
+fn test_stats() {
+    let mut cache = TestCache::default();
+    assert_eq!(cache.stats(), CacheStats::default());
+
+    cache.insert(1);
+    cache.insert(2);
+
+    cache.lookup(|x| if *x == 1 { Some(()) } else { None });
+    cache.lookup(|x| if *x == 2 { Some(()) } else { None });
+    cache.lookup(|x| if *x == 3 { Some(()) } else { None });
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 2);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.evictions, 0, "Nothing evicted yet.");
+
+    cache.insert(3);
+    cache.insert(4);
+    assert!(cache.fetch(|x| *x == 3).is_some());
+    assert!(!cache.touch(|x| *x == 1));
+
+    assert_eq!(
+        cache.stats(),
+        CacheStats {
+            hits: 3,
+            misses: 2,
+            evictions: 2,
+        }
+    );
+
+    cache.reset_stats();
+    assert_eq!(cache.stats(), CacheStats::default(), "Counters reset.");
+}
+
+#[test]
+fn test_zero_capacity_not_counted() {
+    let mut cache = LRUCache::<i32, 0>::new();
+
+    assert_eq!(cache.insert(1), Some(1));
+    assert_eq!(cache.stats().evictions, 0, "Nothing was stored to evict.");
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "std")]
+
+use std::sync::Arc;
+use std::thread;
+
+use lru_rs::SyncLRUCache;
+
+type TestCache = SyncLRUCache<i32, 4>;
+
+#[test]
+fn test_insert_get() {
+    let cache = TestCache::new();
+
+    cache.insert(1);
+    cache.insert(2);
+    assert_eq!(cache.get(|x| *x == 1), Some(1), "Cache hit.");
+    assert_eq!(cache.get(|x| *x == 3), None, "Cache miss.");
+    assert_eq!(
+        cache.lookup(|x| if *x == 2 { Some(*x * 2) } else { None }),
+        Some(4)
+    );
+
+    assert_eq!(cache.into_inner().items(), [2, 1]);
+}
+
+#[test]
+fn test_threads() {
+    let cache = Arc::new(TestCache::new());
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let cache = Arc::clone(&cache);
+
+            thread::spawn(move || {
+                for i in 0..100 {
+                    cache.insert(t * 100 + i);
+                    cache.get(|x| *x % 2 == 0);
+                    assert!(cache.len() <= cache.capacity());
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(cache.len(), 4, "Bounded by capacity.");
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "std")]
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use lru_rs::{Clock, TtlLRUCache};
+
+// A clock that only moves when told to
+#[derive(Clone)]
+struct FakeClock(Rc<Cell<Instant>>);
+
+impl FakeClock {
+    fn new() -> Self {
+        FakeClock(Rc::new(Cell::new(Instant::now())))
+    }
+
+    fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+type TestCache = TtlLRUCache<i32, 4, FakeClock>;
+
+#[test]
+fn test_zero_ttl() {
+    let mut cache = TestCache::with_clock(FakeClock::new());
+
+    cache.insert(1, Duration::ZERO);
+    assert_eq!(cache.get(|x| *x == 1), None, "Expired immediately.");
+    assert!(cache.is_empty(), "Expired value evicted on access.");
+}
+
+#[test]
+fn test_expiry() {
+    let clock = FakeClock::new();
+    let mut cache = TestCache::with_clock(clock.clone());
+
+    cache.insert(1, Duration::from_secs(10));
+    cache.insert(2, Duration::from_secs(20));
+    cache.insert(3, Duration::MAX);
+
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(cache.get(|x| *x == 1), Some(&1), "Not expired yet.");
+
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(
+        cache.lookup(|x| if *x == 1 { Some(*x) } else { None }),
+        None,
+        "Expired after its TTL, even though recently used."
+    );
+    assert_eq!(cache.len(), 2);
+
+    clock.advance(Duration::from_secs(1000));
+    cache.purge_expired();
+    assert_eq!(cache.len(), 1, "Only the value that never expires is left.");
+    assert_eq!(cache.get(|x| *x == 3), Some(&3));
+}
+
+#[test]
+fn test_insert_replaces_expired_tail() {
+    let clock = FakeClock::new();
+    let mut cache = TestCache::with_clock(clock.clone());
+
+    cache.insert(1, Duration::from_secs(1));
+    cache.insert(2, Duration::from_secs(100));
+    cache.insert(3, Duration::from_secs(100));
+    cache.insert(4, Duration::from_secs(100));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(
+        cache.insert(5, Duration::from_secs(100)),
+        None,
+        "Expired tail made room."
+    );
+    assert_eq!(cache.insert(6, Duration::from_secs(100)), Some(2));
+}
+
+#[test]
+fn test_expired_dropped_lazily() {
+    let clock = FakeClock::new();
+    let mut cache = TestCache::with_clock(clock.clone());
+
+    cache.insert(1, Duration::from_secs(100));
+    cache.insert(2, Duration::from_secs(1));
+    cache.insert(3, Duration::from_secs(1));
+    cache.insert(4, Duration::from_secs(100));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(|x| *x == 4), Some(&4));
+    assert_eq!(cache.len(), 4, "Nothing walked past, nothing dropped.");
+
+    assert_eq!(cache.get(|x| *x == 1), Some(&1));
+    assert_eq!(cache.len(), 2, "Expired values walked past are dropped.");
+
+    cache.insert(5, Duration::from_secs(1));
+    clock.advance(Duration::from_secs(1));
+    cache.insert(6, Duration::from_secs(100));
+    assert_eq!(cache.len(), 4, "Untouched expired value kept.");
+
+    cache.purge_expired();
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get(|x| *x == 5), None);
+}
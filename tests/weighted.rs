@@ -0,0 +1,49 @@
+#![cfg(feature = "alloc")]
+
+use lru_rs::WeightedLRUCache;
+
+#[test]
+fn test_insert_evicts_by_weight() {
+    let mut cache = WeightedLRUCache::with_max_weight(4);
+
+    assert!(cache.insert("a", 3).is_empty(), "Fits in the budget.");
+    assert_eq!(cache.weight(), 3);
+
+    assert_eq!(cache.insert("b", 3), ["a"], "First value evicted to fit.");
+    assert_eq!(cache.items(), ["b"]);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.weight(), 3);
+}
+
+#[test]
+fn test_insert_evicts_many() {
+    let mut cache = WeightedLRUCache::with_max_weight(10);
+    cache.insert(1, 2);
+    cache.insert(2, 2);
+    cache.insert(3, 2);
+    cache.insert(4, 2);
+    cache.touch(|x| *x == 1);
+
+    assert_eq!(cache.insert(5, 7), [2, 3, 4], "Evicted in LRU order.");
+    assert_eq!(cache.items(), [5, 1]);
+    assert_eq!(cache.weight(), 9);
+
+    assert_eq!(cache.insert(6, 11), [6], "Too heavy to ever fit.");
+    assert_eq!(cache.items(), [5, 1], "Nothing evicted for it.");
+
+    assert!(
+        cache.insert(7, 0).is_empty(),
+        "Weightless values always fit."
+    );
+    assert_eq!(cache.front(), Some(&7));
+}
+
+#[test]
+fn test_insert_unbounded_budget() {
+    let mut cache = WeightedLRUCache::with_max_weight(usize::MAX);
+    cache.insert("a", usize::MAX - 1);
+
+    assert_eq!(cache.insert("b", 2), ["a"], "Budget check cannot overflow.");
+    assert_eq!(cache.items(), ["b"]);
+    assert_eq!(cache.weight(), 2);
+}